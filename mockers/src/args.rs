@@ -0,0 +1,84 @@
+//! Access to arguments of mocked method by index, used by reactions
+//! modifying arguments passed by mutable reference.
+
+use crate::shared::MaybeSend;
+
+/// Index of mocked method argument, `self` is not counted.
+///
+/// Index is a type-level value, so that argument type is known at compile
+/// time. Use `ARG0`, `ARG1` and so on to get one.
+#[derive(Clone, Copy, Debug)]
+pub struct ArgIndex<const N: usize>;
+
+pub const ARG0: ArgIndex<0> = ArgIndex;
+pub const ARG1: ArgIndex<1> = ArgIndex;
+pub const ARG2: ArgIndex<2> = ArgIndex;
+pub const ARG3: ArgIndex<3> = ArgIndex;
+pub const ARG4: ArgIndex<4> = ArgIndex;
+pub const ARG5: ArgIndex<5> = ArgIndex;
+pub const ARG6: ArgIndex<6> = ArgIndex;
+pub const ARG7: ArgIndex<7> = ArgIndex;
+pub const ARG8: ArgIndex<8> = ArgIndex;
+pub const ARG9: ArgIndex<9> = ArgIndex;
+pub const ARG10: ArgIndex<10> = ArgIndex;
+pub const ARG11: ArgIndex<11> = ArgIndex;
+
+/// Gives access to N-th element of arguments tuple.
+pub trait ArgAt<const N: usize> {
+    type Arg;
+    fn arg_at(&mut self) -> &mut Self::Arg;
+}
+
+macro_rules! impl_arg_at {
+    ($all:tt { $(($n:tt, $T:ident)),* }) => {
+        $(impl_arg_at!(@one $n, $T, $all);)*
+    };
+    (@one $n:tt, $T:ident, ($($All:ident),*)) => {
+        impl<$($All),*> ArgAt<$n> for ($($All,)*) {
+            type Arg = $T;
+            fn arg_at(&mut self) -> &mut $T {
+                &mut self.$n
+            }
+        }
+    };
+}
+
+impl_arg_at!((A0) { (0, A0) });
+impl_arg_at!((A0, A1) { (0, A0), (1, A1) });
+impl_arg_at!((A0, A1, A2) { (0, A0), (1, A1), (2, A2) });
+impl_arg_at!((A0, A1, A2, A3) { (0, A0), (1, A1), (2, A2), (3, A3) });
+impl_arg_at!((A0, A1, A2, A3, A4) { (0, A0), (1, A1), (2, A2), (3, A3), (4, A4) });
+impl_arg_at!((A0, A1, A2, A3, A4, A5) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5)
+});
+impl_arg_at!((A0, A1, A2, A3, A4, A5, A6) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5), (6, A6)
+});
+impl_arg_at!((A0, A1, A2, A3, A4, A5, A6, A7) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5), (6, A6), (7, A7)
+});
+impl_arg_at!((A0, A1, A2, A3, A4, A5, A6, A7, A8) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5), (6, A6), (7, A7), (8, A8)
+});
+impl_arg_at!((A0, A1, A2, A3, A4, A5, A6, A7, A8, A9) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5), (6, A6), (7, A7), (8, A8), (9, A9)
+});
+impl_arg_at!((A0, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5), (6, A6), (7, A7), (8, A8), (9, A9),
+    (10, A10)
+});
+impl_arg_at!((A0, A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11) {
+    (0, A0), (1, A1), (2, A2), (3, A3), (4, A4), (5, A5), (6, A6), (7, A7), (8, A8), (9, A9),
+    (10, A10), (11, A11)
+});
+
+/// Function modifying arguments tuple before reaction is run.
+#[cfg(not(feature = "sync"))]
+pub type ArgModifier<Args> = Box<dyn FnMut(&mut Args)>;
+/// Function modifying arguments tuple before reaction is run.
+#[cfg(feature = "sync")]
+pub type ArgModifier<Args> = Box<dyn FnMut(&mut Args) + Send>;
+
+pub(crate) fn arg_modifier<Args, F: FnMut(&mut Args) + MaybeSend + 'static>(func: F) -> ArgModifier<Args> {
+    Box::new(func)
+}
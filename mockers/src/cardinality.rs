@@ -2,6 +2,8 @@ use std::cmp::Ordering;
 use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
 use std::ops::{RangeInclusive, RangeToInclusive};
 
+use crate::MaybeSend;
+
 /// Result of checking call count against cardinality constraints
 #[derive(PartialEq, Eq, Debug)]
 pub enum CardinalityCheckResult {
@@ -21,7 +23,7 @@ pub enum CardinalityCheckResult {
     Wrong,
 }
 
-pub trait Cardinality {
+pub trait Cardinality: MaybeSend {
     fn check(&self, count: u32) -> CardinalityCheckResult;
     fn describe(&self) -> String;
     fn describe_upper_bound(&self) -> String;
@@ -69,14 +69,18 @@ macro_rules! mock_clone {
     ($mock_name:ident, $handle_name:ident) => {
         #[cfg(test)]
         impl Clone for $mock_name {
+            #[track_caller]
             fn clone(&self) -> Self {
                 let method_data = ::mockers::MethodData {
                     mock_id: self.mock_id,
                     mock_type_id: 0usize,
                     method_name: "clone",
                     type_param_ids: vec![],
+                    default_result: None,
+                    record_args: None,
+                    caller: Some(::std::panic::Location::caller()),
                 };
-                let action = self.scenario.borrow_mut().verify0(method_data);
+                let action = ::mockers::shared::lock(&self.scenario).verify0(method_data);
                 action()
             }
         }
@@ -84,8 +88,9 @@ macro_rules! mock_clone {
         #[cfg(test)]
         impl $crate::CloneMock<$mock_name> for $handle_name {
             #[allow(dead_code)]
+            #[track_caller]
             fn clone(&self) -> ::mockers::CallMatch0<$mock_name> {
-                ::mockers::CallMatch0::new(self.mock_id, 0usize, "clone", vec![])
+                ::mockers::CallMatch0::new(&self.scenario, self.mock_id, 0usize, "clone", vec![])
             }
         }
     };
@@ -1,3 +1,5 @@
+//! Runtime choice of ANSI colors used in failure messages.
+
 // Copied from 'colorify' package because it has no plain 'bold' variant.
 #[macro_export]
 macro_rules! colored {
@@ -95,3 +97,84 @@ macro_rules! colored {
         concat!("\x1b[1;97m", $s, "\x1b[0m")
     };
 }
+
+use std::env;
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+/// Whether failure messages use ANSI colors, see `Scenario::set_colors`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Use colors only when standard error is a terminal, unless they are
+    /// disabled with `NO_COLOR` or `CLICOLOR=0` environment variables.
+    /// `CLICOLOR_FORCE` enables colors even when output isn't a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Returns whether colors should be used. Environment and terminal are
+    /// checked once, result is reused by all later calls.
+    pub fn enabled(self) -> bool {
+        static AUTO: OnceLock<bool> = OnceLock::new();
+        match self {
+            ColorChoice::Auto => *AUTO.get_or_init(detect),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+fn detect() -> bool {
+    let set = |name| env::var_os(name).filter(|value| !value.is_empty());
+    if set("NO_COLOR").is_some() {
+        return false;
+    }
+    if set("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+        return true;
+    }
+    set("CLICOLOR").map_or(true, |value| value != "0") && std::io::stderr().is_terminal()
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Style {
+    Bold,
+    Red,
+    Green,
+    Orange,
+}
+
+impl Style {
+    fn code(self) -> &'static str {
+        match self {
+            Style::Bold => "\x1b[1m",
+            Style::Red => "\x1b[31m",
+            Style::Green => "\x1b[32m",
+            Style::Orange => "\x1b[33m",
+        }
+    }
+
+    /// Wraps value, so that it is formatted with this style if `colors` is true.
+    pub(crate) fn paint<T: fmt::Display>(self, value: T, colors: bool) -> Painted<T> {
+        Painted { style: self, value, colors }
+    }
+}
+
+pub(crate) struct Painted<T> {
+    style: Style,
+    value: T,
+    colors: bool,
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.colors {
+            write!(f, "{}{}\x1b[0m", self.style.code(), self.value)
+        } else {
+            self.value.fmt(f)
+        }
+    }
+}
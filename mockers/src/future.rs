@@ -0,0 +1,90 @@
+//! Support for mocking async methods.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::shared::MaybeSend;
+
+/// Boxed future which must be `Send` only when `sync` feature is enabled.
+/// It is kept by `MockFuture` of `async fn` methods and methods returning
+/// boxed future without `Send` bound.
+#[cfg(not(feature = "sync"))]
+pub type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+/// Boxed future which must be `Send` only when `sync` feature is enabled.
+/// It is kept by `MockFuture` of `async fn` methods and methods returning
+/// boxed future without `Send` bound.
+#[cfg(feature = "sync")]
+pub type LocalBoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Boxed `Send` future, it is kept by `MockFuture` of methods returning
+/// `Pin<Box<dyn Future<Output = T> + Send>>`.
+pub type SendBoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Boxed future type which may hold future `F`.
+pub trait BoxFuture<F>: Future + Unpin {
+    fn boxed(future: F) -> Self;
+}
+
+impl<F: Future + MaybeSend + 'static> BoxFuture<F> for LocalBoxFuture<F::Output> {
+    fn boxed(future: F) -> Self {
+        Box::pin(future)
+    }
+}
+
+// With `sync` feature `SendBoxFuture` is the same type as `LocalBoxFuture`.
+#[cfg(not(feature = "sync"))]
+impl<F: Future + Send + 'static> BoxFuture<F> for SendBoxFuture<F::Output> {
+    fn boxed(future: F) -> Self {
+        Box::pin(future)
+    }
+}
+
+/// Future returned by reactions of mocked async methods.
+///
+/// It either resolves to value given in reaction immediately or
+/// delegates to arbitrary future provided by user and kept in box `B`.
+pub struct MockFuture<T, B = LocalBoxFuture<T>>(State<T, B>);
+
+enum State<T, B> {
+    Ready(Option<T>),
+    Pending(B),
+}
+
+impl<T, B> MockFuture<T, B> {
+    /// Creates future which resolves to given value on first poll.
+    pub fn ready(value: T) -> Self {
+        MockFuture(State::Ready(Some(value)))
+    }
+
+    /// Creates future which resolves when given future does.
+    pub fn pending<F: Future<Output = T>>(future: F) -> Self
+    where
+        B: BoxFuture<F>,
+    {
+        MockFuture(State::Pending(B::boxed(future)))
+    }
+}
+
+impl<T: Default, B> Default for MockFuture<T, B> {
+    fn default() -> Self {
+        MockFuture::ready(T::default())
+    }
+}
+
+// Ready value is never pinned and pending future is boxed,
+// so it is safe to move `MockFuture` after it was polled.
+impl<T, B: Unpin> Unpin for MockFuture<T, B> {}
+
+impl<T, B: Future<Output = T> + Unpin> Future for MockFuture<T, B> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        match self.get_mut().0 {
+            State::Ready(ref mut value) => {
+                Poll::Ready(value.take().expect("MockFuture is polled after completion"))
+            }
+            State::Pending(ref mut future) => Pin::new(future).poll(cx),
+        }
+    }
+}
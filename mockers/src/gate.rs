@@ -0,0 +1,178 @@
+//! Latch used to control when blocking reactions complete.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Latch which is closed when created and may be opened once by test.
+///
+/// Mocked calls blocked on gate using `and_block_until` reaction complete
+/// when gate is opened. Gate is a handle, its clones refer to the same latch,
+/// so it may be opened from another thread.
+#[derive(Clone, Default)]
+pub struct Gate(Arc<GateState>);
+
+#[derive(Default)]
+struct GateState {
+    inner: Mutex<GateInner>,
+    opened: Condvar,
+}
+
+#[derive(Default)]
+struct GateInner {
+    open: bool,
+    wakers: Vec<Waker>,
+}
+
+impl Gate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates gate which is opened by timer thread after given duration.
+    pub(crate) fn opened_after(duration: Duration) -> Self {
+        let gate = Gate::new();
+        timer().schedule(Instant::now() + duration, gate.clone());
+        gate
+    }
+
+    /// Opens gate, releasing all calls waiting for it.
+    pub fn open(&self) {
+        let wakers = {
+            let mut inner = self.lock();
+            inner.open = true;
+            std::mem::take(&mut inner.wakers)
+        };
+        self.0.opened.notify_all();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.lock().open
+    }
+
+    /// Blocks current thread until gate is opened.
+    pub fn wait(&self) {
+        let mut inner = self.lock();
+        while !inner.open {
+            inner = self.0.opened.wait(inner).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Returns future which resolves when gate is opened.
+    pub fn wait_async(&self) -> GateWait {
+        GateWait(self.clone())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GateInner> {
+        self.0.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Future returned by `Gate::wait_async`.
+pub struct GateWait(Gate);
+
+impl Future for GateWait {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let mut inner = self.0.lock();
+        if inner.open {
+            Poll::Ready(())
+        } else {
+            if !inner.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                inner.wakers.push(cx.waker().clone());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+/// Gate which must be opened at given time.
+struct Deadline {
+    at: Instant,
+    gate: Gate,
+}
+
+impl PartialEq for Deadline {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+impl Eq for Deadline {}
+impl PartialOrd for Deadline {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Deadline {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.at.cmp(&other.at)
+    }
+}
+
+/// Queue of deadlines served by single background thread, which is
+/// started when first deadline is scheduled.
+struct Timer {
+    deadlines: Mutex<BinaryHeap<Reverse<Deadline>>>,
+    changed: Condvar,
+}
+
+fn timer() -> &'static Timer {
+    static TIMER: OnceLock<Timer> = OnceLock::new();
+    TIMER.get_or_init(|| {
+        // Thread waits until initialization is finished on first `timer()` call.
+        thread::spawn(|| timer().run());
+        Timer {
+            deadlines: Mutex::new(BinaryHeap::new()),
+            changed: Condvar::new(),
+        }
+    })
+}
+
+impl Timer {
+    fn schedule(&self, at: Instant, gate: Gate) {
+        self.lock().push(Reverse(Deadline { at, gate }));
+        self.changed.notify_one();
+    }
+
+    fn run(&self) {
+        let mut deadlines = self.lock();
+        loop {
+            let now = Instant::now();
+            let mut due = Vec::new();
+            while deadlines.peek().is_some_and(|deadline| deadline.0.at <= now) {
+                due.push(deadlines.pop().unwrap().0.gate);
+            }
+            if !due.is_empty() {
+                // Gates are opened without lock held, because waking tasks
+                // may schedule new deadlines.
+                drop(deadlines);
+                for gate in due {
+                    gate.open();
+                }
+                deadlines = self.lock();
+                continue;
+            }
+
+            deadlines = match deadlines.peek() {
+                Some(deadline) => {
+                    let timeout = deadline.0.at - now;
+                    self.changed.wait_timeout(deadlines, timeout).unwrap_or_else(|e| e.into_inner()).0
+                }
+                None => self.changed.wait(deadlines).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BinaryHeap<Reverse<Deadline>>> {
+        self.deadlines.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,12 +1,13 @@
 #![cfg_attr(feature = "nightly", feature(specialization))]
 
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::marker::PhantomData;
-use std::rc::{Rc, Weak};
 
-use std::fmt::Write;
+use std::future::Future;
 use std::ops::DerefMut;
+use std::panic::Location;
+use std::time::Duration;
 
 use itertools::Itertools;
 
@@ -18,15 +19,41 @@ pub mod matchers;
 #[macro_use]
 pub mod clone;
 pub mod type_info;
+pub mod shared;
+pub mod args;
+pub mod future;
+pub mod gate;
+pub mod sequence;
+pub mod mode;
+pub mod record;
+pub mod report;
 
 pub use crate::type_info::TypeInfo;
 pub use dbg::DebugOnStable;
 pub use clone::CloneMock;
+pub use shared::MaybeSend;
+pub use sequence::{Sequence, SequencedExpectation};
+pub use mode::MockMode;
+pub use gate::Gate;
+pub use colors::ColorChoice;
+pub use report::{Failure, VerificationReport};
 
 use crate::cardinality::{Cardinality, CardinalityCheckResult};
+use crate::colors::Style;
 use crate::dbg::dbg;
+use crate::future::{BoxFuture, LocalBoxFuture, MockFuture, SendBoxFuture};
+use crate::mode::DefaultResult;
+use crate::record::{ArgsRecorder, CallRecord, RecordedCall};
+use crate::report::{ArgMismatch, ArgsMismatch, CallCountViolation, OtherMockExpectation, OutOfOrder};
+use crate::report::{ReceivedCall, SourceLocation, UnexpectedCall, UnsatisfiedExpectation, WrongCallCount};
+use crate::args::{arg_modifier, ArgAt, ArgIndex, ArgModifier};
+use crate::sequence::UnsatisfiedPrerequisite;
+use crate::shared::{lock, Lock, Shared, WeakShared};
 
-pub type MockRef = (usize, Rc<RefCell<ScenarioInternals>>);
+/// Reference to scenario internals which is shared by scenario, mocks and handles.
+pub type ScenarioRef = Shared<Lock<ScenarioInternals>>;
+
+pub type MockRef = (usize, ScenarioRef);
 
 thread_local! {
     // Mapping from mock_type_id of 'extern' block mock to corresponding mock object.
@@ -36,7 +63,10 @@ thread_local! {
 
 macro_rules! define_actions {
     ($action_clone:ident { $($Arg:ident),* }) => {
-        type $action_clone<$($Arg,)* T> = Rc<RefCell<dyn FnMut($($Arg,)*) -> T>>;
+        #[cfg(not(feature = "sync"))]
+        type $action_clone<$($Arg,)* T> = Shared<Lock<dyn FnMut($($Arg,)*) -> T>>;
+        #[cfg(feature = "sync")]
+        type $action_clone<$($Arg,)* T> = Shared<Lock<dyn FnMut($($Arg,)*) -> T + Send>>;
     }
 }
 
@@ -45,6 +75,14 @@ define_actions!(ActionClone1 { Arg0 });
 define_actions!(ActionClone2 { Arg0, Arg1 });
 define_actions!(ActionClone3 { Arg0, Arg1, Arg2 });
 define_actions!(ActionClone4 { Arg0, Arg1, Arg2, Arg3 });
+define_actions!(ActionClone5 { Arg0, Arg1, Arg2, Arg3, Arg4 });
+define_actions!(ActionClone6 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5 });
+define_actions!(ActionClone7 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5, Arg6 });
+define_actions!(ActionClone8 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5, Arg6, Arg7 });
+define_actions!(ActionClone9 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5, Arg6, Arg7, Arg8 });
+define_actions!(ActionClone10 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5, Arg6, Arg7, Arg8, Arg9 });
+define_actions!(ActionClone11 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5, Arg6, Arg7, Arg8, Arg9, Arg10 });
+define_actions!(ActionClone12 { Arg0, Arg1, Arg2, Arg3, Arg4, Arg5, Arg6, Arg7, Arg8, Arg9, Arg10, Arg11 });
 
 pub trait CallMatch {
     fn matches_args(&self, call: &Call) -> bool;
@@ -64,49 +102,113 @@ pub trait CallMatch {
             && self.get_type_param_ids() == &call.method_data.type_param_ids[..]
     }
     fn validate(&self, call: &Call) -> Vec<Result<(), String>>;
+    /// Whether all argument matchers accept any value, see `MatchArg::matches_any`.
+    fn matches_any_args(&self) -> bool {
+        false
+    }
+    /// Passes call arguments to argument matchers, see `MatchArg::capture`.
+    fn capture(&self, _call: &Call) {}
     fn get_mock_id(&self) -> usize;
     fn get_mock_type_id(&self) -> usize;
     fn get_method_name(&self) -> &'static str;
     fn get_type_param_ids(&self) -> &[usize];
     fn describe(&self) -> String;
+    /// Returns descriptions of argument matchers.
+    fn describe_args(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Returns place where call match is created, that is where
+    /// expectation is declared.
+    fn location(&self) -> Option<&'static Location<'static>> {
+        None
+    }
 }
 
-pub trait Expectation {
+pub trait Expectation: MaybeSend {
     fn call_match(&self) -> &dyn CallMatch;
+    fn matches(&self, call: &Call) -> bool {
+        self.call_match().matches(call)
+    }
     fn is_satisfied(&self) -> bool;
+    /// Whether expectation is used up by previous calls and can't accept more.
+    fn is_exhausted(&self) -> bool {
+        false
+    }
+    /// Checks whether expectation may accept one more call.
+    #[allow(clippy::result_large_err)]
+    fn check_call_count(&self, _mock_name: &str) -> Result<(), WrongCallCount> {
+        Ok(())
+    }
     fn satisfy(&mut self, call: Call, mock_name: &str) -> Box<dyn Satisfy>;
     fn describe(&self) -> String;
+    /// Returns prerequisite which prevents expectation from matching call
+    /// because of calls order.
+    fn unsatisfied_prerequisite(&self, _call: &Call) -> Option<UnsatisfiedPrerequisite> {
+        None
+    }
+}
+
+/// Something which may be passed to `Scenario::expect`: either single
+/// expectation or `Sequence` of them.
+pub trait IntoExpectations {
+    fn into_expectations(self) -> Vec<Box<dyn Expectation>>;
+}
+impl<E: Expectation + 'static> IntoExpectations for E {
+    fn into_expectations(self) -> Vec<Box<dyn Expectation>> {
+        vec![Box::new(self)]
+    }
 }
 
 pub struct ExpectationNever<CM: CallMatch> {
     call_match: CM,
 }
-impl<CM: CallMatch> Expectation for ExpectationNever<CM> {
+impl<CM: CallMatch + MaybeSend> Expectation for ExpectationNever<CM> {
     fn call_match(&self) -> &dyn CallMatch {
         &self.call_match
     }
     fn is_satisfied(&self) -> bool {
         true
     }
+    fn check_call_count(&self, mock_name: &str) -> Result<(), WrongCallCount> {
+        Err(WrongCallCount {
+            mock_name: mock_name.to_owned(),
+            method_name: self.call_match().get_method_name().to_owned(),
+            count: 1,
+            violation: CallCountViolation::NeverExpected,
+            location: self.call_match().location().map(SourceLocation::from),
+            call_location: None,
+        })
+    }
     fn satisfy(&mut self, _call: Call, mock_name: &str) -> Box<dyn Satisfy> {
-        panic!(
-            "{}.{} should never be called",
-            mock_name,
-            self.call_match().get_method_name()
-        );
+        panic!("{}", self.check_call_count(mock_name).unwrap_err());
     }
     fn describe(&self) -> String {
         format!("{} should never be called", self.call_match.describe())
     }
 }
+impl<CM: CallMatch + MaybeSend + 'static> ExpectationNever<CM> {
+    /// Adds expectation to the end of given sequence.
+    pub fn in_sequence(self, sequence: &Sequence) -> SequencedExpectation {
+        SequencedExpectation::new(self).in_sequence(sequence)
+    }
+}
 
 pub trait Satisfy {
     fn satisfy(self: Box<Self>) -> *mut u8;
 }
 
+/// Produces default method result for unexpected calls to nice mocks.
+struct SatisfyDefault(DefaultResult);
+
+impl Satisfy for SatisfyDefault {
+    fn satisfy(self: Box<Self>) -> *mut u8 {
+        (self.0)()
+    }
+}
+
 macro_rules! define_all {
     (
-        ($call_match:ident, $reaction:ident,
+        ($call_match:ident, $async_call_match:ident, $reaction:ident,
           $action_clone:ident,
           $satisfy:ident, $satisfy_clone:ident,
           $expectation:ident, $expectation_times:ident)
@@ -131,40 +233,57 @@ macro_rules! define_all {
 
         impl<$($Arg,)* Res> Satisfy for $satisfy_clone<$($Arg,)* Res> {
             fn satisfy(self: Box<Self>) -> *mut u8 {
-                let result = self.action.borrow_mut().deref_mut()($(self.$arg,)*);
+                let result = lock(&self.action).deref_mut()($(self.$arg,)*);
                 Box::into_raw(Box::new(result)) as *mut u8
             }
         }
 
         #[must_use]
         pub struct $call_match<$($Arg,)* Res> {
+            scenario: WeakShared<Lock<ScenarioInternals>>,
             mock_id: usize,
             mock_type_id: usize,
             method_name: &'static str,
             type_param_ids: Vec<usize>,
-            $($arg: Box<dyn MatchArg<$Arg>>,)*
-            _phantom: PhantomData<Res>,
+            $($arg: BoxedMatchArg<$Arg>,)*
+            modifiers: Vec<ArgModifier<($($Arg,)*)>>,
+            location: &'static Location<'static>,
+            _phantom: PhantomData<fn() -> Res>,
         }
 
         impl<$($Arg,)* Res> $call_match<$($Arg,)* Res> {
+            /// Creates call match, remembering location of caller
+            /// which is reported in error messages.
             #[allow(clippy::too_many_arguments)]
+            #[track_caller]
             pub fn new(
+                scenario: &ScenarioRef,
                 mock_id: usize,
                 mock_type_id: usize,
                 method_name: &'static str,
                 type_param_ids: Vec<usize>,
-                $($arg: Box<dyn MatchArg<$Arg>>,)*
+                $($arg: BoxedMatchArg<$Arg>,)*
             ) -> Self {
                 $call_match {
+                    scenario: Shared::downgrade(scenario),
                     mock_id: mock_id,
                     mock_type_id: mock_type_id,
                     method_name: method_name,
                     type_param_ids: type_param_ids,
                     $($arg: $arg,)*
+                    modifiers: Vec::new(),
+                    location: Location::caller(),
                     _phantom: PhantomData,
                 }
             }
 
+            /// Applies argument modifiers, see `and_assign_with`.
+            fn modify_args(&mut self, args: &mut ($($Arg,)*)) {
+                for modifier in &mut self.modifiers {
+                    modifier(args);
+                }
+            }
+
             fn get_args_ref(call: &Call) -> &($($Arg,)*) {
                 unsafe { &mut *(call.args_ptr as *mut ($($Arg,)*)) }
             }
@@ -172,6 +291,55 @@ macro_rules! define_all {
             fn get_args(mut call: Call) -> Box<($($Arg,)*)> {
                 unsafe { Box::from_raw(call.take_args() as *mut ($($Arg,)*)) }
             }
+
+            /// Returns whether scenario recorded matching call.
+            /// Call recording must be enabled with `Scenario::record_calls`.
+            ///
+            /// Calls of methods which don't record arguments match only when
+            /// all argument matchers accept any value, like `ANY`. Otherwise they
+            /// don't match and warning is printed when scenario is dropped.
+            pub fn was_called(&self) -> bool {
+                self.call_count() > 0
+            }
+
+            /// Returns number of matching calls recorded by scenario.
+            /// Call recording must be enabled with `Scenario::record_calls`,
+            /// see `was_called` for calls with unrecorded arguments.
+            pub fn call_count(&self) -> usize {
+                let scenario = self.scenario.upgrade().expect("scenario is dead");
+                let mut internals = lock(&scenario);
+                internals.warn_unrecorded_args(self);
+                internals.matching_calls(self).count()
+            }
+        }
+
+        impl<$($Arg,)* Res> $call_match<$($Arg,)* Res> {
+            /// Returns matching calls recorded by scenario.
+            /// Call recording must be enabled with `Scenario::record_calls`,
+            /// see `was_called` for calls with unrecorded arguments.
+            ///
+            /// `Args` is tuple of arguments as they are saved: reference
+            /// arguments are saved as owned values using `ToOwned`, so calls
+            /// of `fn log(&self, level: u32, message: &str)` are inspected with
+            /// `calls::<(u32, String)>()`. Panics if `Args` is another type.
+            pub fn calls<Args: Clone + 'static>(&self) -> Vec<RecordedCall<Args>> {
+                let scenario = self.scenario.upgrade().expect("scenario is dead");
+                let mut internals = lock(&scenario);
+                internals.warn_unrecorded_args(self);
+                internals.matching_calls(self).map(|record| {
+                    let mock_name = internals.get_mock_name(record.method_data.mock_id).to_string();
+                    let args = record.saved_args().unwrap_or_else(|saved_type| panic!(
+                        "arguments of `{}.{}` are recorded as `{}`, not `{}`",
+                        mock_name, record.method_data.method_name, saved_type, std::any::type_name::<Args>()
+                    ));
+                    RecordedCall {
+                        mock_name,
+                        method_name: record.method_data.method_name,
+                        args_debug: record.args_debug.clone(),
+                        args,
+                    }
+                }).collect()
+            }
         }
 
         #[must_use]
@@ -195,6 +363,8 @@ macro_rules! define_all {
             call_match: $call_match<$($Arg,)* Res>,
             cardinality: Box<dyn Cardinality>,
             count: u32,
+            tail: Option<$action_clone<$($Arg,)* Res>>,
+            retire_on_saturation: bool,
         }
 
         impl<$($Arg,)* Res> $expectation_times<$($Arg,)* Res> {
@@ -208,30 +378,88 @@ macro_rules! define_all {
                     action: action,
                     cardinality: cardinality,
                     count: 0,
+                    tail: None,
+                    retire_on_saturation: false,
                 }
             }
+
+            /// Stops matching calls once expected number of calls is reached,
+            /// so that further calls go to other expectations instead of failing.
+            pub fn retire_on_saturation(mut self) -> Self {
+                self.retire_on_saturation = true;
+                self
+            }
+        }
+
+        impl<$($Arg,)* Res: Clone + MaybeSend + 'static> $expectation_times<$($Arg,)* Res> {
+            /// Returns clone of given value for calls made after expected
+            /// number of calls is reached instead of failing.
+            pub fn then_return_clone(mut self, result: Res) -> Self {
+                #[allow(unused_variables)]
+                let tail: $action_clone<$($Arg,)* Res> = Shared::new(Lock::new(move |$($arg,)*| result.clone()));
+                self.tail = Some(tail);
+                self
+            }
+        }
+
+        impl<$($Arg,)* Res: 'static> $expectation_times<$($Arg,)* Res> {
+            /// Panics with given message for calls made after expected
+            /// number of calls is reached.
+            pub fn then_panic(mut self, msg: String) -> Self {
+                #[allow(unused_variables)]
+                let tail: $action_clone<$($Arg,)* Res> = Shared::new(Lock::new(move |$($arg,)*| -> Res { panic!("{}", msg) }));
+                self.tail = Some(tail);
+                self
+            }
+
+            fn is_past_upper_bound(&self) -> bool {
+                self.cardinality.check(self.count + 1) == CardinalityCheckResult::Wrong
+            }
         }
 
         impl<$($Arg: 'static,)* Res: 'static> Expectation for $expectation_times<$($Arg,)* Res> {
             fn call_match(&self) -> &dyn CallMatch {
                 &self.call_match
             }
+            fn matches(&self, call: &Call) -> bool {
+                !(self.retire_on_saturation && self.is_past_upper_bound()) && self.call_match.matches(call)
+            }
             fn is_satisfied(&self) -> bool {
                 self.cardinality.check(self.count) == CardinalityCheckResult::Satisfied
             }
+            fn is_exhausted(&self) -> bool {
+                self.tail.is_none() && self.count > 0 && self.is_past_upper_bound()
+            }
+            fn check_call_count(&self, mock_name: &str) -> Result<(), WrongCallCount> {
+                if self.tail.is_none() && self.is_past_upper_bound() {
+                    return Err(WrongCallCount {
+                        mock_name: mock_name.to_owned(),
+                        method_name: self.call_match().get_method_name().to_owned(),
+                        count: self.count + 1,
+                        violation: CallCountViolation::TooMany {
+                            expected: self.cardinality.describe_upper_bound(),
+                        },
+                        location: self.call_match().location().map(SourceLocation::from),
+                        call_location: None,
+                    });
+                }
+                Ok(())
+            }
             fn satisfy(&mut self, call: Call, mock_name: &str) -> Box<dyn Satisfy> {
-                self.count += 1;
-                if self.cardinality.check(self.count) == CardinalityCheckResult::Wrong {
-                    panic!(
-                        "{}.{} is called for the {} time, but expected to be {}",
-                        mock_name,
-                        self.call_match().get_method_name(),
-                        format_ordinal(self.count),
-                        self.cardinality.describe_upper_bound()
-                    );
+                if let Err(failure) = self.check_call_count(mock_name) {
+                    panic!("{}", failure);
+                }
+                let tail = match self.tail {
+                    Some(ref tail) if self.is_past_upper_bound() => Some(tail.clone()),
+                    _ => None,
+                };
+                if tail.is_none() {
+                    self.count += 1;
                 }
-                let ($($arg,)*) = *$call_match::<$($Arg,)* Res>::get_args(call);
-                let action = self.action.clone();
+                let mut args = $call_match::<$($Arg,)* Res>::get_args(call);
+                self.call_match.modify_args(&mut args);
+                let ($($arg,)*) = *args;
+                let action = tail.unwrap_or_else(|| self.action.clone());
                 Box::new(
                     $satisfy_clone {
                         action,
@@ -249,13 +477,20 @@ macro_rules! define_all {
             }
         }
 
+        impl<$($Arg: 'static,)* Res: 'static> $expectation_times<$($Arg,)* Res> {
+            /// Adds expectation to the end of given sequence.
+            pub fn in_sequence(self, sequence: &Sequence) -> SequencedExpectation {
+                SequencedExpectation::new(self).in_sequence(sequence)
+            }
+        }
+
         #[must_use]
         pub struct $expectation<$($Arg,)* Res, F: FnOnce($($Arg,)*) -> Res> {
             call_match: $call_match<$($Arg,)* Res>,
             action: Option<F>,
         }
 
-        impl<$($Arg: 'static,)* Res: 'static, F: FnOnce($($Arg,)*) -> Res + 'static> Expectation
+        impl<$($Arg: 'static,)* Res: 'static, F: FnOnce($($Arg,)*) -> Res + MaybeSend + 'static> Expectation
         for $expectation<$($Arg,)* Res, F> {
             fn call_match(&self) -> &dyn CallMatch {
                 &self.call_match
@@ -263,31 +498,50 @@ macro_rules! define_all {
             fn is_satisfied(&self) -> bool {
                 self.action.is_none()
             }
+            fn is_exhausted(&self) -> bool {
+                self.action.is_none()
+            }
+            fn check_call_count(&self, mock_name: &str) -> Result<(), WrongCallCount> {
+                if self.action.is_none() {
+                    return Err(WrongCallCount {
+                        mock_name: mock_name.to_owned(),
+                        method_name: self.call_match().get_method_name().to_owned(),
+                        count: 2,
+                        violation: CallCountViolation::AlreadyCalled,
+                        location: self.call_match().location().map(SourceLocation::from),
+                        call_location: None,
+                    });
+                }
+                Ok(())
+            }
             fn satisfy(&mut self, call: Call, mock_name: &str) -> Box<dyn Satisfy> {
-                match self.action.take() {
-                    Some(action) => {
-                        let ($($arg,)*) = *$call_match::<$($Arg,)* Res>::get_args(call);
-                        Box::new(
-                            $satisfy {
-                                action,
-                                $($arg,)*
-                            }
-                        )
-                    }
-                    None => {
-                        panic!(
-                            "{}.{} was already called earlier",
-                            mock_name,
-                            self.call_match().get_method_name()
-                        );
-                    }
+                if let Err(failure) = self.check_call_count(mock_name) {
+                    panic!("{}", failure);
                 }
+                let action = self.action.take().unwrap();
+                let mut args = $call_match::<$($Arg,)* Res>::get_args(call);
+                self.call_match.modify_args(&mut args);
+                let ($($arg,)*) = *args;
+                Box::new(
+                    $satisfy {
+                        action,
+                        $($arg,)*
+                    }
+                )
             }
             fn describe(&self) -> String {
                 self.call_match.describe()
             }
         }
 
+        impl<$($Arg: 'static,)* Res: 'static, F: FnOnce($($Arg,)*) -> Res + MaybeSend + 'static>
+        $expectation<$($Arg,)* Res, F> {
+            /// Adds expectation to the end of given sequence.
+            pub fn in_sequence(self, sequence: &Sequence) -> SequencedExpectation {
+                SequencedExpectation::new(self).in_sequence(sequence)
+            }
+        }
+
         impl<$($Arg: 'static,)* Res: 'static> $call_match<$($Arg,)* Res> {
             pub fn and_return(self, result: Res)
             -> $expectation<$($Arg,)* Res, impl FnOnce($($Arg,)*) -> Res> {
@@ -310,7 +564,7 @@ macro_rules! define_all {
             pub fn and_call<F>(self, func: F)
             -> $expectation<$($Arg,)* Res, impl FnOnce($($Arg,)*) -> Res>
             where
-                F: FnOnce($($Arg,)*) -> Res + 'static,
+                F: FnOnce($($Arg,)*) -> Res + MaybeSend + 'static,
             {
                 $expectation {
                     call_match: self,
@@ -321,28 +575,147 @@ macro_rules! define_all {
             pub fn never(self) -> ExpectationNever<Self> {
                 ExpectationNever { call_match: self }
             }
+
+            /// Sleeps for given duration and then returns value.
+            pub fn and_delay(self, duration: Duration, result: Res)
+            -> $expectation<$($Arg,)* Res, impl FnOnce($($Arg,)*) -> Res> {
+                #[allow(unused_variables)]
+                $expectation {
+                    call_match: self,
+                    action: Some(move |$($arg,)*| {
+                        std::thread::sleep(duration);
+                        result
+                    }),
+                }
+            }
+
+            /// Blocks until given gate is opened and then returns value.
+            pub fn and_block_until(self, gate: &Gate, result: Res)
+            -> $expectation<$($Arg,)* Res, impl FnOnce($($Arg,)*) -> Res> {
+                let gate = gate.clone();
+                #[allow(unused_variables)]
+                $expectation {
+                    call_match: self,
+                    action: Some(move |$($arg,)*| {
+                        gate.wait();
+                        result
+                    }),
+                }
+            }
+
+            /// Calls function with argument passed by mutable reference before
+            /// reaction is run. May be chained with any other reaction:
+            ///
+            /// ```rust,ignore
+            /// handle.read(ANY).and_assign_with(ARG0, |buf: &mut [u8]| buf[0] = 1).and_return(1)
+            /// ```
+            pub fn and_assign_with<const N: usize, T: ?Sized, F>(mut self, _index: ArgIndex<N>, mut func: F) -> Self
+            where
+                ($($Arg,)*): ArgAt<N>,
+                <($($Arg,)*) as ArgAt<N>>::Arg: DerefMut<Target = T>,
+                F: FnMut(&mut T) + MaybeSend + 'static,
+            {
+                self.modifiers.push(arg_modifier(move |args: &mut ($($Arg,)*)| {
+                    func(<($($Arg,)*) as ArgAt<N>>::arg_at(args).deref_mut())
+                }));
+                self
+            }
+
+            /// Sets value of argument passed by mutable reference before
+            /// reaction is run, see `and_assign_with`.
+            pub fn and_set_arg<const N: usize, T>(self, index: ArgIndex<N>, value: T) -> Self
+            where
+                ($($Arg,)*): ArgAt<N>,
+                <($($Arg,)*) as ArgAt<N>>::Arg: DerefMut<Target = T>,
+                T: Clone + MaybeSend + 'static,
+            {
+                self.and_assign_with(index, move |arg: &mut T| *arg = value.clone())
+            }
+
+            /// Copies data into beginning of slice argument passed by mutable reference
+            /// before reaction is run, see `and_assign_with`.
+            pub fn and_copy_into_slice<const N: usize, T>(self, index: ArgIndex<N>, data: &[T]) -> Self
+            where
+                ($($Arg,)*): ArgAt<N>,
+                <($($Arg,)*) as ArgAt<N>>::Arg: DerefMut<Target = [T]>,
+                T: Clone + MaybeSend + 'static,
+            {
+                let data = data.to_vec();
+                self.and_assign_with(index, move |slice: &mut [T]| {
+                    assert!(
+                        slice.len() >= data.len(),
+                        "slice argument is too short to copy data into: {} < {}",
+                        slice.len(),
+                        data.len()
+                    );
+                    slice[..data.len()].clone_from_slice(&data);
+                })
+            }
         }
 
-        impl<$($Arg,)* Res: Clone + 'static> $call_match<$($Arg,)* Res> {
+        impl<$($Arg,)* Res: Clone + MaybeSend + 'static> $call_match<$($Arg,)* Res> {
             pub fn and_return_clone(self, result: Res) -> $reaction<$($Arg,)* Res> {
                 #[allow(unused_variables)]
                 $reaction {
                     call_match: self,
-                    action: Rc::new(RefCell::new(move |$($arg,)*| result.clone())),
+                    action: Shared::new(Lock::new(move |$($arg,)*| result.clone())),
                 }
             }
         }
 
+        impl<$($Arg,)* Res: MaybeSend + 'static> $call_match<$($Arg,)* Res> {
+            /// Returns given values one by one, expecting as many calls
+            /// as there are values. Use `then_return_clone` or `then_panic`
+            /// to react to further calls.
+            pub fn and_return_each(self, results: Vec<Res>) -> $expectation_times<$($Arg,)* Res> {
+                let count = results.len() as u32;
+                let mut results = results.into_iter();
+                #[allow(unused_variables)]
+                let action: $action_clone<$($Arg,)* Res> = Shared::new(Lock::new(move |$($arg,)*| {
+                    results.next().expect("values are exhausted")
+                }));
+                $expectation_times::new(self, action, Box::new(count))
+            }
+        }
+
         impl<$($Arg,)* Res> $call_match<$($Arg,)* Res> {
             pub fn and_call_clone<F>(self, func: F) -> $reaction<$($Arg,)* Res>
             where
-                F: FnMut($($Arg,)*) -> Res + 'static,
+                F: FnMut($($Arg,)*) -> Res + MaybeSend + 'static,
             {
                 $reaction {
                     call_match: self,
-                    action: Rc::new(RefCell::new(func)),
+                    action: Shared::new(Lock::new(func)),
                 }
             }
+
+            /// Returns value created by given function on each call,
+            /// so that result type doesn't have to be clonable.
+            pub fn and_return_with<F>(self, mut func: F) -> $reaction<$($Arg,)* Res>
+            where
+                F: FnMut() -> Res + MaybeSend + 'static,
+            {
+                #[allow(unused_variables)]
+                self.and_call_clone(move |$($arg,)*| func())
+            }
+        }
+
+        impl<$($Arg,)* T, E> $call_match<$($Arg,)* Result<T, E>> {
+            /// Returns `Ok` with value created by given function on each call.
+            pub fn and_return_ok_with<F>(self, mut func: F) -> $reaction<$($Arg,)* Result<T, E>>
+            where
+                F: FnMut() -> T + MaybeSend + 'static,
+            {
+                self.and_return_with(move || Ok(func()))
+            }
+
+            /// Returns `Err` with error created by given function on each call.
+            pub fn and_return_err_with<F>(self, mut func: F) -> $reaction<$($Arg,)* Result<T, E>>
+            where
+                F: FnMut() -> E + MaybeSend + 'static,
+            {
+                self.and_return_with(move || Err(func()))
+            }
         }
 
         impl<$($Arg,)* Res: Default + 'static> $call_match<$($Arg,)* Res> {
@@ -350,11 +723,243 @@ macro_rules! define_all {
                 #[allow(unused_variables, clippy::redundant_closure)]
                 $reaction {
                     call_match: self,
-                    action: Rc::new(RefCell::new(|$($arg,)*| Res::default())),
+                    action: Shared::new(Lock::new(|$($arg,)*| Res::default())),
                 }
             }
         }
 
+        /// Call match for async methods.
+        ///
+        /// Reactions take awaited result type, they are wrapped into
+        /// `MockFuture` automatically.
+        #[must_use]
+        pub struct $async_call_match<$($Arg,)* Res, B = LocalBoxFuture<Res>>($call_match<$($Arg,)* MockFuture<Res, B>>);
+
+        impl<$($Arg,)* Res, B> $async_call_match<$($Arg,)* Res, B> {
+            #[allow(clippy::too_many_arguments)]
+            #[track_caller]
+            pub fn new(
+                scenario: &ScenarioRef,
+                mock_id: usize,
+                mock_type_id: usize,
+                method_name: &'static str,
+                type_param_ids: Vec<usize>,
+                $($arg: BoxedMatchArg<$Arg>,)*
+            ) -> Self {
+                $async_call_match($call_match::new(
+                    scenario, mock_id, mock_type_id, method_name, type_param_ids, $($arg,)*
+                ))
+            }
+
+            /// See `CallMatch0::was_called`.
+            pub fn was_called(&self) -> bool {
+                self.0.was_called()
+            }
+
+            /// See `CallMatch0::call_count`.
+            pub fn call_count(&self) -> usize {
+                self.0.call_count()
+            }
+        }
+
+        impl<$($Arg: 'static,)* Res: 'static, B: 'static> $async_call_match<$($Arg,)* Res, B> {
+            /// See `CallMatch0::and_assign_with`.
+            pub fn and_assign_with<const N: usize, T: ?Sized, F>(self, index: ArgIndex<N>, func: F) -> Self
+            where
+                ($($Arg,)*): ArgAt<N>,
+                <($($Arg,)*) as ArgAt<N>>::Arg: DerefMut<Target = T>,
+                F: FnMut(&mut T) + MaybeSend + 'static,
+            {
+                $async_call_match(self.0.and_assign_with(index, func))
+            }
+
+            /// See `CallMatch0::and_set_arg`.
+            pub fn and_set_arg<const N: usize, T>(self, index: ArgIndex<N>, value: T) -> Self
+            where
+                ($($Arg,)*): ArgAt<N>,
+                <($($Arg,)*) as ArgAt<N>>::Arg: DerefMut<Target = T>,
+                T: Clone + MaybeSend + 'static,
+            {
+                $async_call_match(self.0.and_set_arg(index, value))
+            }
+
+            /// See `CallMatch0::and_copy_into_slice`.
+            pub fn and_copy_into_slice<const N: usize, T>(self, index: ArgIndex<N>, data: &[T]) -> Self
+            where
+                ($($Arg,)*): ArgAt<N>,
+                <($($Arg,)*) as ArgAt<N>>::Arg: DerefMut<Target = [T]>,
+                T: Clone + MaybeSend + 'static,
+            {
+                $async_call_match(self.0.and_copy_into_slice(index, data))
+            }
+        }
+
+        impl<$($Arg,)* Res, B> $async_call_match<$($Arg,)* Res, B> {
+            /// See `CallMatch0::calls`.
+            pub fn calls<Args: Clone + 'static>(&self) -> Vec<RecordedCall<Args>> {
+                self.0.calls()
+            }
+        }
+
+        impl<$($Arg: 'static,)* Res: 'static, B: 'static> $async_call_match<$($Arg,)* Res, B> {
+            pub fn and_return(self, result: Res)
+            -> $expectation<$($Arg,)* MockFuture<Res, B>, impl FnOnce($($Arg,)*) -> MockFuture<Res, B>> {
+                self.0.and_return(MockFuture::ready(result))
+            }
+
+            /// Returns given future from mocked method, it may be used
+            /// to control when method call completes.
+            pub fn and_return_future<Fut>(self, future: Fut)
+            -> $expectation<$($Arg,)* MockFuture<Res, B>, impl FnOnce($($Arg,)*) -> MockFuture<Res, B>>
+            where
+                Fut: Future<Output = Res> + 'static,
+                B: BoxFuture<Fut>,
+            {
+                self.0.and_return(MockFuture::pending(future))
+            }
+
+            pub fn and_panic(self, msg: String)
+            -> $expectation<$($Arg,)* MockFuture<Res, B>, impl FnOnce($($Arg,)*) -> MockFuture<Res, B>> {
+                self.0.and_panic(msg)
+            }
+
+            pub fn and_call<F>(self, func: F)
+            -> $expectation<$($Arg,)* MockFuture<Res, B>, impl FnOnce($($Arg,)*) -> MockFuture<Res, B>>
+            where
+                F: FnOnce($($Arg,)*) -> Res + MaybeSend + 'static,
+            {
+                self.0.and_call(move |$($arg,)*| MockFuture::ready(func($($arg,)*)))
+            }
+
+            pub fn never(self) -> ExpectationNever<$call_match<$($Arg,)* MockFuture<Res, B>>> {
+                self.0.never()
+            }
+        }
+
+        // Futures waiting for gate are `Send`, so they may be kept by both
+        // `LocalBoxFuture` and `SendBoxFuture`.
+        impl<$($Arg: 'static,)* Res: Send + 'static, B> $async_call_match<$($Arg,)* Res, B>
+        where
+            B: BoxFuture<SendBoxFuture<Res>> + 'static,
+        {
+            /// Returns future which resolves to value after given duration
+            /// since call. Future doesn't block executor while waiting.
+            pub fn and_delay(self, duration: Duration, result: Res)
+            -> $expectation<$($Arg,)* MockFuture<Res, B>, impl FnOnce($($Arg,)*) -> MockFuture<Res, B>> {
+                #[allow(unused_variables)]
+                self.0.and_call(move |$($arg,)*| {
+                    let gate = Gate::opened_after(duration);
+                    MockFuture::pending::<SendBoxFuture<Res>>(Box::pin(async move {
+                        gate.wait_async().await;
+                        result
+                    }))
+                })
+            }
+
+            /// Returns future which resolves to value when given gate is opened.
+            /// Future doesn't block executor while waiting.
+            pub fn and_block_until(self, gate: &Gate, result: Res)
+            -> $expectation<$($Arg,)* MockFuture<Res, B>, impl FnOnce($($Arg,)*) -> MockFuture<Res, B>> {
+                let gate = gate.clone();
+                self.0.and_return(MockFuture::pending::<SendBoxFuture<Res>>(Box::pin(async move {
+                    gate.wait_async().await;
+                    result
+                })))
+            }
+        }
+
+        impl<$($Arg,)* Res: Clone + MaybeSend + 'static, B> $async_call_match<$($Arg,)* Res, B> {
+            pub fn and_return_clone(self, result: Res) -> $reaction<$($Arg,)* MockFuture<Res, B>> {
+                #[allow(unused_variables)]
+                self.0.and_call_clone(move |$($arg,)*| MockFuture::ready(result.clone()))
+            }
+        }
+
+        impl<$($Arg,)* Res, B> $async_call_match<$($Arg,)* Res, B> {
+            pub fn and_call_clone<F>(self, mut func: F) -> $reaction<$($Arg,)* MockFuture<Res, B>>
+            where
+                F: FnMut($($Arg,)*) -> Res + MaybeSend + 'static,
+            {
+                self.0.and_call_clone(move |$($arg,)*| MockFuture::ready(func($($arg,)*)))
+            }
+
+            /// See `CallMatch0::and_return_with`.
+            pub fn and_return_with<F>(self, mut func: F) -> $reaction<$($Arg,)* MockFuture<Res, B>>
+            where
+                F: FnMut() -> Res + MaybeSend + 'static,
+            {
+                self.0.and_return_with(move || MockFuture::ready(func()))
+            }
+        }
+
+        impl<$($Arg,)* T, E, B> $async_call_match<$($Arg,)* Result<T, E>, B> {
+            /// See `CallMatch0::and_return_ok_with`.
+            pub fn and_return_ok_with<F>(self, mut func: F) -> $reaction<$($Arg,)* MockFuture<Result<T, E>, B>>
+            where
+                F: FnMut() -> T + MaybeSend + 'static,
+            {
+                self.and_return_with(move || Ok(func()))
+            }
+
+            /// See `CallMatch0::and_return_err_with`.
+            pub fn and_return_err_with<F>(self, mut func: F) -> $reaction<$($Arg,)* MockFuture<Result<T, E>, B>>
+            where
+                F: FnMut() -> E + MaybeSend + 'static,
+            {
+                self.and_return_with(move || Err(func()))
+            }
+        }
+
+        impl<$($Arg,)* Res: Default + 'static, B: 'static> $async_call_match<$($Arg,)* Res, B> {
+            pub fn and_return_default(self) -> $reaction<$($Arg,)* MockFuture<Res, B>> {
+                #[allow(unused_variables)]
+                self.0.and_call_clone(|$($arg,)*| MockFuture::ready(Res::default()))
+            }
+        }
+
+        impl<$($Arg: 'static,)* Res: 'static> DefaultFor<$call_match<$($Arg,)* Res>> {
+            pub fn and_return_clone(self, result: Res) where Res: Clone + MaybeSend {
+                #[allow(unused_variables)]
+                self.and_call_clone(move |$($arg,)*| result.clone())
+            }
+
+            pub fn and_call_clone<F>(self, func: F)
+            where
+                F: FnMut($($Arg,)*) -> Res + MaybeSend + 'static,
+            {
+                let reaction = self.call_match.and_call_clone(func);
+                lock(&self.internals).default_actions.push(Box::new(reaction.times(..)));
+            }
+
+            pub fn and_return_default(self) where Res: Default {
+                #[allow(unused_variables)]
+                self.and_call_clone(|$($arg,)*| Res::default())
+            }
+        }
+
+        impl<$($Arg: 'static,)* Res: 'static, B: 'static> DefaultFor<$async_call_match<$($Arg,)* Res, B>> {
+            fn into_sync(self) -> DefaultFor<$call_match<$($Arg,)* MockFuture<Res, B>>> {
+                DefaultFor { internals: self.internals, call_match: self.call_match.0 }
+            }
+
+            pub fn and_return_clone(self, result: Res) where Res: Clone + MaybeSend {
+                #[allow(unused_variables)]
+                self.into_sync().and_call_clone(move |$($arg,)*| MockFuture::ready(result.clone()))
+            }
+
+            pub fn and_call_clone<F>(self, mut func: F)
+            where
+                F: FnMut($($Arg,)*) -> Res + MaybeSend + 'static,
+            {
+                self.into_sync().and_call_clone(move |$($arg,)*| MockFuture::ready(func($($arg,)*)))
+            }
+
+            pub fn and_return_default(self) where Res: Default {
+                #[allow(unused_variables)]
+                self.and_call_clone(|$($arg,)*| Res::default())
+            }
+        }
+
         impl<$($Arg,)* Res> CallMatch for $call_match<$($Arg,)* Res> {
             fn matches_args(&self, call: &Call) -> bool {
                 assert!(
@@ -371,6 +976,13 @@ macro_rules! define_all {
                 let __args = Self::get_args_ref(call);
                 vec![$(self.$arg.matches(&__args.$n)),*]
             }
+            fn matches_any_args(&self) -> bool {
+                true $(&& self.$arg.matches_any())*
+            }
+            fn capture(&self, call: &Call) {
+                let __args = Self::get_args_ref(call);
+                $(self.$arg.capture(&__args.$n);)*
+            }
             fn get_mock_id(&self) -> usize {
                 self.mock_id
             }
@@ -384,27 +996,68 @@ macro_rules! define_all {
                 &self.type_param_ids
             }
             fn describe(&self) -> String {
-                let args: &[&dyn std::fmt::Display] = &[$(&self.$arg.describe()),*];
-                format!("{}({})", self.get_method_name(), args.iter().format(", "))
+                format!("{}({})", self.get_method_name(), self.describe_args().iter().format(", "))
+            }
+            fn describe_args(&self) -> Vec<String> {
+                vec![$(self.$arg.describe()),*]
+            }
+            fn location(&self) -> Option<&'static Location<'static>> {
+                Some(self.location)
             }
         }
     }
 }
 
-define_all!((CallMatch0, Reaction0, ActionClone0, Satisfy0, SatisfyClone0, Expectation0, ExpectationTimes0) {
+define_all!((CallMatch0, AsyncCallMatch0, Reaction0, ActionClone0, Satisfy0, SatisfyClone0, Expectation0, ExpectationTimes0) {
 });
-define_all!((CallMatch1, Reaction1, ActionClone1, Satisfy1, SatisfyClone1, Expectation1, ExpectationTimes1) {
+define_all!((CallMatch1, AsyncCallMatch1, Reaction1, ActionClone1, Satisfy1, SatisfyClone1, Expectation1, ExpectationTimes1) {
     (0, arg0, Arg0)
 });
-define_all!((CallMatch2, Reaction2, ActionClone2, Satisfy2, SatisfyClone2, Expectation2, ExpectationTimes2) {
+define_all!((CallMatch2, AsyncCallMatch2, Reaction2, ActionClone2, Satisfy2, SatisfyClone2, Expectation2, ExpectationTimes2) {
     (0, arg0, Arg0), (1, arg1, Arg1)
 });
-define_all!((CallMatch3, Reaction3, ActionClone3, Satisfy3, SatisfyClone3, Expectation3, ExpectationTimes3) {
+define_all!((CallMatch3, AsyncCallMatch3, Reaction3, ActionClone3, Satisfy3, SatisfyClone3, Expectation3, ExpectationTimes3) {
     (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2)
 });
-define_all!((CallMatch4, Reaction4, ActionClone4, Satisfy4, SatisfyClone4, Expectation4, ExpectationTimes4) {
+define_all!((CallMatch4, AsyncCallMatch4, Reaction4, ActionClone4, Satisfy4, SatisfyClone4, Expectation4, ExpectationTimes4) {
     (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3)
 });
+define_all!((CallMatch5, AsyncCallMatch5, Reaction5, ActionClone5, Satisfy5, SatisfyClone5, Expectation5, ExpectationTimes5) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4)
+});
+define_all!((CallMatch6, AsyncCallMatch6, Reaction6, ActionClone6, Satisfy6, SatisfyClone6, Expectation6, ExpectationTimes6) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5)
+});
+define_all!((CallMatch7, AsyncCallMatch7, Reaction7, ActionClone7, Satisfy7, SatisfyClone7, Expectation7, ExpectationTimes7) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6)
+});
+define_all!((CallMatch8, AsyncCallMatch8, Reaction8, ActionClone8, Satisfy8, SatisfyClone8, Expectation8, ExpectationTimes8) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7)
+});
+define_all!((CallMatch9, AsyncCallMatch9, Reaction9, ActionClone9, Satisfy9, SatisfyClone9, Expectation9, ExpectationTimes9) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+    (8, arg8, Arg8)
+});
+define_all!((CallMatch10, AsyncCallMatch10, Reaction10, ActionClone10, Satisfy10, SatisfyClone10, Expectation10, ExpectationTimes10) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+    (8, arg8, Arg8), (9, arg9, Arg9)
+});
+define_all!((CallMatch11, AsyncCallMatch11, Reaction11, ActionClone11, Satisfy11, SatisfyClone11, Expectation11, ExpectationTimes11) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+    (8, arg8, Arg8), (9, arg9, Arg9), (10, arg10, Arg10)
+});
+define_all!((CallMatch12, AsyncCallMatch12, Reaction12, ActionClone12, Satisfy12, SatisfyClone12, Expectation12, ExpectationTimes12) {
+    (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+    (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+    (8, arg8, Arg8), (9, arg9, Arg9), (10, arg10, Arg10), (11, arg11, Arg11)
+});
 
 /// Argument matcher
 ///
@@ -414,57 +1067,34 @@ define_all!((CallMatch4, Reaction4, ActionClone4, Satisfy4, SatisfyClone4, Expec
 pub trait MatchArg<T> {
     fn matches(&self, arg: &T) -> Result<(), String>;
     fn describe(&self) -> String;
-}
 
-#[derive(Default)]
-pub struct Sequence {
-    expectations: Vec<Box<dyn Expectation>>,
-}
-impl Sequence {
-    pub fn new() -> Self {
-        Sequence {
-            expectations: Vec::new(),
-        }
-    }
+    /// Called for argument of call which matched whole expectation, right
+    /// before expectation reaction is run. Used by capturing matchers,
+    /// see `matchers::Captor`.
+    fn capture(&self, _arg: &T) {}
 
-    pub fn expect<E: Expectation + 'static>(&mut self, expectation: E) {
-        assert!(!expectation.is_satisfied());
-        self.expectations.push(Box::new(expectation));
+    /// Whether matcher accepts any value without looking at it. Such
+    /// matchers match recorded calls even if their arguments aren't recorded.
+    fn matches_any(&self) -> bool {
+        false
     }
 }
-impl Expectation for Sequence {
-    fn call_match(&self) -> &dyn CallMatch {
-        self.expectations[0].call_match()
-    }
-    fn is_satisfied(&self) -> bool {
-        self.expectations.is_empty()
-    }
-    fn satisfy(&mut self, call: Call, mock_name: &str) -> Box<dyn Satisfy> {
-        let (res, remove) = {
-            let exp = &mut self.expectations[0];
-            let res = exp.satisfy(call, mock_name);
-            (res, exp.is_satisfied())
-        };
 
-        if remove {
-            self.expectations.remove(0);
-        }
-
-        res
-    }
-    fn describe(&self) -> String {
-        self.expectations[0].describe()
-    }
-}
+/// Boxed argument matcher as it is stored in call match.
+#[cfg(not(feature = "sync"))]
+pub type BoxedMatchArg<T> = Box<dyn MatchArg<T>>;
+/// Boxed argument matcher as it is stored in call match.
+#[cfg(feature = "sync")]
+pub type BoxedMatchArg<T> = Box<dyn MatchArg<T> + Send>;
 
 pub trait MockHandle {
-    fn new(id: usize, scenario_int: Rc<RefCell<ScenarioInternals>>) -> Self;
+    fn new(id: usize, scenario_int: ScenarioRef) -> Self;
 }
 
 pub trait Mock {
     type Handle: MockHandle;
 
-    fn new(id: usize, scenario_int: Rc<RefCell<ScenarioInternals>>) -> Self;
+    fn new(id: usize, scenario_int: ScenarioRef) -> Self;
     fn mocked_class_name() -> &'static str;
 }
 
@@ -472,15 +1102,77 @@ pub trait Mocked {
     type MockImpl: Mock;
 }
 
+/// Allows mock to forward calls to real implementation of mocked trait.
+///
+/// It is implemented for mocked traits which can be made into objects.
+pub trait Delegating: Mocked {
+    /// Real implementation type, i.e. `dyn Trait`.
+    type Delegate: ?Sized;
+
+    fn set_delegate(mock: &mut Self::MockImpl, delegate: Box<Self::Delegate>);
+}
+
+/// Type of real implementation used by partial mocks, it is `dyn Trait`
+/// with `Send + Sync` bounds added when `sync` feature is enabled.
+#[cfg(not(feature = "sync"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! delegate_type {
+    ($($t:tt)+) => { dyn $($t)+ };
+}
+/// Type of real implementation used by partial mocks, it is `dyn Trait`
+/// with `Send + Sync` bounds added when `sync` feature is enabled.
+#[cfg(feature = "sync")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! delegate_type {
+    ($($t:tt)+) => { dyn $($t)+ + Send + Sync };
+}
+
 pub struct ScenarioInternals {
     expectations: Vec<Box<dyn Expectation>>,
 
+    /// Sequences all new expectations are added to, see `Scenario::in_sequence`.
+    active_sequences: Vec<Sequence>,
+
+    /// Reactions used for calls not matching any expectation, see `Scenario::default_for`.
+    default_actions: Vec<Box<dyn Expectation>>,
+
     next_mock_id: usize,
 
     /// Mapping from mock ID to mock name.
-    mock_names: HashMap<usize, Rc<String>>,
+    mock_names: HashMap<usize, Shared<String>>,
+    /// Mapping from mock ID to mock mode, mocks are strict unless specified otherwise.
+    mock_modes: HashMap<usize, MockMode>,
     /// Set of used mock names used to quicly check for conflicts.
-    allocated_names: HashSet<Rc<String>>,
+    allocated_names: HashSet<Shared<String>>,
+
+    /// Warnings about unexpected calls to naggy mocks, printed when scenario is dropped.
+    warnings: Vec<String>,
+
+    /// Whether all calls seen by scenario are recorded, see `Scenario::record_calls`.
+    record_calls: bool,
+    /// IDs of partial mocks, calls they forward to real implementation are
+    /// recorded even when `record_calls` is off.
+    partial_mocks: HashSet<usize>,
+    /// Recorded calls.
+    recorded_calls: Vec<CallRecord>,
+
+    /// Number of calls preceding failed one which are reported, see `Scenario::show_recent_calls`.
+    recent_calls_limit: usize,
+    /// Last calls seen by scenario, including current one.
+    recent_calls: VecDeque<ReceivedCall>,
+
+    /// Whether exhausted expectations stop matching calls, see `Scenario::retire_on_saturation`.
+    retire_on_saturation: bool,
+
+    /// Whether failed calls are collected instead of panicking, see `Scenario::collect_failures`.
+    collect_failures: bool,
+    /// Failures of calls collected so far.
+    failures: Vec<Failure>,
+
+    /// Whether failure messages use ANSI colors, see `Scenario::set_colors`.
+    pub(crate) colors: ColorChoice,
 }
 
 impl ScenarioInternals {
@@ -490,25 +1182,35 @@ impl ScenarioInternals {
         id
     }
 
-    pub fn create_mock<T: Mock>(int: &Rc<RefCell<Self>>) -> (T, T::Handle) {
-        let mut internals = int.borrow_mut();
+    pub fn create_mock<T: Mock>(int: &ScenarioRef) -> (T, T::Handle) {
+        Self::create_mock_with_mode::<T>(int, MockMode::Strict)
+    }
+
+    pub fn create_mock_with_mode<T: Mock>(int: &ScenarioRef, mode: MockMode) -> (T, T::Handle) {
+        let mut internals = lock(int);
         let mock_id = internals.get_next_mock_id();
         internals.generate_name_for_class(mock_id, T::mocked_class_name());
+        internals.mock_modes.insert(mock_id, mode);
         (T::new(mock_id, int.clone()), T::Handle::new(mock_id, int.clone()))
     }
 
-    pub fn create_mock_with_id<T: Mock>(int: &Rc<RefCell<Self>>, mock_id: usize) -> (T, T::Handle) {
+    pub fn create_mock_with_id<T: Mock>(int: &ScenarioRef, mock_id: usize) -> (T, T::Handle) {
         (T::new(mock_id, int.clone()), T::Handle::new(mock_id, int.clone()))
     }
 
-    pub fn create_named_mock<T: Mock>(int: &Rc<RefCell<Self>>, name: String) -> (T, T::Handle) {
-        let mut internals = int.borrow_mut();
+    pub fn create_named_mock<T: Mock>(int: &ScenarioRef, name: String) -> (T, T::Handle) {
+        Self::create_named_mock_with_mode::<T>(int, name, MockMode::Strict)
+    }
+
+    pub fn create_named_mock_with_mode<T: Mock>(int: &ScenarioRef, name: String, mode: MockMode) -> (T, T::Handle) {
+        let mut internals = lock(int);
         let mock_id = internals.get_next_mock_id();
         internals.register_name(mock_id, name);
+        internals.mock_modes.insert(mock_id, mode);
         (T::new(mock_id, int.clone()), T::Handle::new(mock_id, int.clone()))
     }
 
-    pub fn create_mock_for<T: ?Sized>(int: &Rc<RefCell<Self>>)
+    pub fn create_mock_for<T: ?Sized>(int: &ScenarioRef)
         -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
     where
         &'static T: Mocked,
@@ -517,7 +1219,7 @@ impl ScenarioInternals {
     }
 
     pub fn create_named_mock_for<T: ?Sized>(
-        int: &Rc<RefCell<Self>>,
+        int: &ScenarioRef,
         name: String,
     ) -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
     where
@@ -526,11 +1228,46 @@ impl ScenarioInternals {
         Self::create_named_mock::<<&'static T as Mocked>::MockImpl>(int, name)
     }
 
+    pub fn create_partial_mock_for<T: ?Sized>(
+        int: &ScenarioRef,
+        delegate: Box<<&'static T as Delegating>::Delegate>,
+    ) -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Delegating,
+    {
+        let mock_id = lock(int).next_mock_id;
+        let (mut mock, handle) = Self::create_mock_for::<T>(int);
+        <&'static T as Delegating>::set_delegate(&mut mock, delegate);
+        lock(int).partial_mocks.insert(mock_id);
+        (mock, handle)
+    }
+
+    pub fn create_mock_for_with_mode<T: ?Sized>(
+        int: &ScenarioRef,
+        mode: MockMode,
+    ) -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        Self::create_mock_with_mode::<<&'static T as Mocked>::MockImpl>(int, mode)
+    }
+
+    pub fn create_named_mock_for_with_mode<T: ?Sized>(
+        int: &ScenarioRef,
+        name: String,
+        mode: MockMode,
+    ) -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        Self::create_named_mock_with_mode::<<&'static T as Mocked>::MockImpl>(int, name, mode)
+    }
+
     pub fn generate_name_for_class(&mut self, mock_id: usize, class_name: &str) {
         for i in 0.. {
             let name = format!("{}#{}", class_name, i);
             if !self.allocated_names.contains(&name) {
-                let name_rc = Rc::new(name);
+                let name_rc = Shared::new(name);
                 self.mock_names.insert(mock_id, name_rc.clone());
                 self.allocated_names.insert(name_rc);
                 break;
@@ -538,29 +1275,57 @@ impl ScenarioInternals {
         }
     }
 
+    fn add_expectations<E: IntoExpectations>(&mut self, expectations: E) {
+        for expectation in expectations.into_expectations() {
+            let expectation = if self.active_sequences.is_empty() {
+                expectation
+            } else {
+                let sequenced = self.active_sequences.iter().fold(
+                    SequencedExpectation::from_boxed(expectation),
+                    SequencedExpectation::in_sequence,
+                );
+                Box::new(sequenced)
+            };
+            self.expectations.push(expectation);
+        }
+    }
+
     fn register_name(&mut self, mock_id: usize, name: String) {
         if self.allocated_names.contains(&name) {
             panic!("Mock name {} already used", name);
         }
-        let name_rc = Rc::new(name);
+        let name_rc = Shared::new(name);
         self.mock_names.insert(mock_id, name_rc.clone());
         self.allocated_names.insert(name_rc);
     }
 }
 
 pub struct Scenario {
-    internals: Rc<RefCell<ScenarioInternals>>,
+    internals: ScenarioRef,
 }
 
 impl Scenario {
     pub fn new() -> Self {
         Scenario {
-            internals: Rc::new(RefCell::new(ScenarioInternals {
+            internals: Shared::new(Lock::new(ScenarioInternals {
                 expectations: Vec::new(),
+                active_sequences: Vec::new(),
+                default_actions: Vec::new(),
                 next_mock_id: 0,
 
                 mock_names: HashMap::new(),
+                mock_modes: HashMap::new(),
                 allocated_names: HashSet::new(),
+                warnings: Vec::new(),
+                record_calls: false,
+                partial_mocks: HashSet::new(),
+                recorded_calls: Vec::new(),
+                recent_calls_limit: 0,
+                recent_calls: VecDeque::new(),
+                retire_on_saturation: false,
+                collect_failures: false,
+                failures: Vec::new(),
+                colors: ColorChoice::Auto,
             })),
         }
     }
@@ -589,37 +1354,165 @@ impl Scenario {
         ScenarioInternals::create_named_mock_for::<T>(&self.internals, name)
     }
 
-    pub fn expect<C: Expectation + 'static>(&self, call: C) {
-        self.internals
-            .borrow_mut()
-            .expectations
-            .push(Box::new(call));
+    /// Creates partial mock which forwards calls not matching any expectation
+    /// to given real implementation. Forwarded calls are recorded, so they
+    /// may be inspected later using `was_called`, `call_count` and `calls`.
+    /// Calls handled by expectations and calls of other mocks are recorded
+    /// only if `record_calls` is enabled.
+    pub fn create_partial_mock_for<T: ?Sized>(
+        &self,
+        delegate: Box<<&'static T as Delegating>::Delegate>,
+    ) -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Delegating,
+    {
+        ScenarioInternals::create_partial_mock_for::<T>(&self.internals, delegate)
+    }
+
+    /// Creates nice mock, see `MockMode::Nice`.
+    pub fn create_nice_mock<T: Mock>(&self) -> (T, T::Handle) {
+        ScenarioInternals::create_mock_with_mode::<T>(&self.internals, MockMode::Nice)
+    }
+
+    /// Creates naggy mock, see `MockMode::Naggy`.
+    pub fn create_naggy_mock<T: Mock>(&self) -> (T, T::Handle) {
+        ScenarioInternals::create_mock_with_mode::<T>(&self.internals, MockMode::Naggy)
+    }
+
+    /// Creates nice mock, see `MockMode::Nice`.
+    pub fn create_nice_mock_for<T: ?Sized>(&self)
+        -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        ScenarioInternals::create_mock_for_with_mode::<T>(&self.internals, MockMode::Nice)
+    }
+
+    /// Creates naggy mock, see `MockMode::Naggy`.
+    pub fn create_naggy_mock_for<T: ?Sized>(&self)
+        -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        ScenarioInternals::create_mock_for_with_mode::<T>(&self.internals, MockMode::Naggy)
+    }
+
+    /// Creates named mock with given mode, see `MockMode`.
+    pub fn create_named_mock_with_mode<T: Mock>(&self, name: String, mode: MockMode) -> (T, T::Handle) {
+        ScenarioInternals::create_named_mock_with_mode::<T>(&self.internals, name, mode)
+    }
+
+    /// Creates named mock with given mode, see `MockMode`.
+    pub fn create_named_mock_for_with_mode<T: ?Sized>(&self, name: String, mode: MockMode)
+        -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        ScenarioInternals::create_named_mock_for_with_mode::<T>(&self.internals, name, mode)
+    }
+
+    pub fn expect<C: IntoExpectations>(&self, call: C) {
+        lock(&self.internals).add_expectations(call);
+    }
+
+    /// Enables recording of all calls made on mocks, so that they
+    /// may be inspected later using `was_called`, `call_count` and `calls`
+    /// methods of call matches.
+    pub fn record_calls(&self) {
+        lock(&self.internals).record_calls = true;
+    }
+
+    /// Makes unexpected call failures list up to `count` calls received
+    /// by scenario before failed one, along with places they are made at.
+    pub fn show_recent_calls(&self, count: usize) {
+        let mut internals = lock(&self.internals);
+        internals.recent_calls_limit = count;
+        internals.recent_calls.clear();
+    }
+
+    /// Makes all expectations stop matching calls once they are exhausted,
+    /// as if `retire_on_saturation` was called on each of them. Further calls
+    /// go to older expectations or are reported as unexpected.
+    pub fn retire_on_saturation(&self) {
+        lock(&self.internals).retire_on_saturation = true;
+    }
+
+    /// Sets default reaction for calls matching given call match.
+    ///
+    /// Default reaction is used for calls which don't match any expectation.
+    /// It may be used any number of times, including zero, and isn't affected
+    /// by checkpoints.
+    pub fn default_for<CM>(&self, call_match: CM) -> DefaultFor<CM> {
+        DefaultFor { internals: self.internals.clone(), call_match }
+    }
+
+    /// Adds all expectations created while returned scope object is alive
+    /// to the end of given sequence.
+    pub fn in_sequence(&self, sequence: &Sequence) -> SequenceScope<'_> {
+        lock(&self.internals).active_sequences.push(sequence.clone());
+        SequenceScope { internals: &self.internals }
+    }
+
+    /// Makes failed calls be collected instead of panicking immediately.
+    ///
+    /// Unexpected calls and calls exceeding expected number of calls return
+    /// default value when method result type implements `Default`, and still
+    /// panic otherwise. Either way failure is remembered and reported by `verify`
+    /// or when scenario is dropped, even if panic was caught by code under test.
+    pub fn collect_failures(&self) {
+        lock(&self.internals).collect_failures = true;
+    }
+
+    /// Chooses whether failure messages use ANSI colors. By default colors
+    /// are used when standard error is a terminal, see `ColorChoice::Auto`.
+    pub fn set_colors(&self, colors: ColorChoice) {
+        lock(&self.internals).colors = colors;
     }
 
     pub fn checkpoint(&self) {
         self.verify_expectations();
-        self.internals.borrow_mut().expectations.clear();
+        lock(&self.internals).expectations.clear();
+    }
+
+    /// Same as `checkpoint`, but returns report with all failures
+    /// instead of panicking.
+    pub fn verify(&self) -> Result<(), VerificationReport> {
+        let report = self.take_report();
+        lock(&self.internals).expectations.clear();
+        if report.is_empty() {
+            Ok(())
+        } else {
+            Err(report)
+        }
     }
 
     pub fn handle(&self) -> ScenarioHandle {
-        ScenarioHandle::new(Rc::downgrade(&self.internals))
+        ScenarioHandle::new(Shared::downgrade(&self.internals))
     }
 
     fn verify_expectations(&self) {
-        let int = self.internals.borrow();
-        let expectations = &int.expectations;
-        let mock_names = &int.mock_names;
-        let mut active_expectations = expectations.iter().filter(|e| !e.is_satisfied()).peekable();
-        if active_expectations.peek().is_some() {
-            let mut s = String::from("Some expectations are not satisfied:\n");
-            for expectation in active_expectations {
-                let mock_name = mock_names
-                    .get(&expectation.call_match().get_mock_id())
-                    .unwrap();
-                s.push_str(&format!("`{}.{}`\n", mock_name, expectation.describe()));
-            }
-            panic!("{}", s);
+        let report = self.take_report();
+        if !report.is_empty() {
+            panic!("{}", report);
+        }
+    }
+
+    /// Takes collected failures and adds unsatisfied expectations to them.
+    fn take_report(&self) -> VerificationReport {
+        let mut int = lock(&self.internals);
+        let mut failures = std::mem::take(&mut int.failures);
+        for expectation in int.expectations.iter().filter(|e| !e.is_satisfied()) {
+            let mock_name = int
+                .mock_names
+                .get(&expectation.call_match().get_mock_id())
+                .unwrap();
+            failures.push(Failure::UnsatisfiedExpectation(UnsatisfiedExpectation {
+                mock_name: mock_name.to_string(),
+                expectation: expectation.describe(),
+                location: expectation.call_match().location().map(SourceLocation::from),
+            }));
         }
+        VerificationReport::new(failures, int.colors.enabled())
     }
 }
 
@@ -635,6 +1528,10 @@ impl Drop for Scenario {
         // expectations. And if we do, then panic-during-drop will cause
         // test to fail with uncomprehensive message like:
         // "(signal: 4, SIGILL: illegal instruction)"
+        for warning in &lock(&self.internals).warnings {
+            eprintln!("{}", warning);
+        }
+
         if std::thread::panicking() {
             return;
         }
@@ -643,12 +1540,31 @@ impl Drop for Scenario {
     }
 }
 
+/// Call match which default reaction is set for, see `Scenario::default_for`.
+#[must_use]
+pub struct DefaultFor<CM> {
+    internals: ScenarioRef,
+    call_match: CM,
+}
+
+/// Scope returned by `Scenario::in_sequence`.
+#[must_use]
+pub struct SequenceScope<'a> {
+    internals: &'a ScenarioRef,
+}
+
+impl<'a> Drop for SequenceScope<'a> {
+    fn drop(&mut self) {
+        lock(self.internals).active_sequences.pop();
+    }
+}
+
 pub struct ScenarioHandle {
-    internals: Weak<RefCell<ScenarioInternals>>,
+    internals: WeakShared<Lock<ScenarioInternals>>,
 }
 
 impl ScenarioHandle {
-    pub fn new(scenario_int: Weak<RefCell<ScenarioInternals>>) -> Self {
+    pub fn new(scenario_int: WeakShared<Lock<ScenarioInternals>>) -> Self {
         Self {
             internals: scenario_int,
         }
@@ -678,12 +1594,62 @@ impl ScenarioHandle {
         ScenarioInternals::create_named_mock_for::<T>(&self.get_internals(), name)
     }
 
-    pub fn expect<C: Expectation + 'static>(&self, call: C) {
+    pub fn create_partial_mock_for<T: ?Sized>(
+        &self,
+        delegate: Box<<&'static T as Delegating>::Delegate>,
+    ) -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Delegating,
+    {
+        ScenarioInternals::create_partial_mock_for::<T>(&self.get_internals(), delegate)
+    }
+
+    pub fn create_nice_mock<T: Mock>(&self) -> (T, T::Handle) {
+        ScenarioInternals::create_mock_with_mode::<T>(&self.get_internals(), MockMode::Nice)
+    }
+
+    pub fn create_naggy_mock<T: Mock>(&self) -> (T, T::Handle) {
+        ScenarioInternals::create_mock_with_mode::<T>(&self.get_internals(), MockMode::Naggy)
+    }
+
+    pub fn create_nice_mock_for<T: ?Sized>(&self)
+        -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        ScenarioInternals::create_mock_for_with_mode::<T>(&self.get_internals(), MockMode::Nice)
+    }
+
+    pub fn create_naggy_mock_for<T: ?Sized>(&self)
+        -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        ScenarioInternals::create_mock_for_with_mode::<T>(&self.get_internals(), MockMode::Naggy)
+    }
+
+    pub fn create_named_mock_with_mode<T: Mock>(&self, name: String, mode: MockMode) -> (T, T::Handle) {
+        ScenarioInternals::create_named_mock_with_mode::<T>(&self.get_internals(), name, mode)
+    }
+
+    pub fn create_named_mock_for_with_mode<T: ?Sized>(&self, name: String, mode: MockMode)
+        -> (<&'static T as Mocked>::MockImpl, <<&'static T as Mocked>::MockImpl as Mock>::Handle)
+    where
+        &'static T: Mocked,
+    {
+        ScenarioInternals::create_named_mock_for_with_mode::<T>(&self.get_internals(), name, mode)
+    }
+
+    pub fn expect<C: IntoExpectations>(&self, call: C) {
         let ints = self.get_internals();
-        ints.borrow_mut().expectations.push(Box::new(call));
+        lock(&ints).add_expectations(call);
+    }
+
+    pub fn default_for<CM>(&self, call_match: CM) -> DefaultFor<CM> {
+        DefaultFor { internals: self.get_internals(), call_match }
     }
 
-    fn get_internals(&self) -> Rc<RefCell<ScenarioInternals>> {
+    fn get_internals(&self) -> ScenarioRef {
         self.internals.upgrade().expect("scenario is dead")
     }
 }
@@ -707,6 +1673,7 @@ impl Drop for Call {
     }
 }
 
+#[derive(Clone)]
 pub struct MethodData {
     /// Unique ID of mock object
     pub mock_id: usize,
@@ -719,19 +1686,44 @@ pub struct MethodData {
 
     /// Type parameters of generic method
     pub type_param_ids: Vec<usize>,
+
+    /// Factory of default result used for unexpected calls to nice mocks,
+    /// `None` if result type doesn't implement `Default`.
+    pub default_result: Option<DefaultResult>,
+
+    /// Functions saving call arguments for call recording,
+    /// `None` if arguments aren't recorded.
+    pub record_args: Option<ArgsRecorder>,
+
+    /// Place where method is called, `None` if it isn't known.
+    pub caller: Option<&'static Location<'static>>,
 }
 
 macro_rules! define_verify {
     (
-        $verify:ident { $(($n:tt, $arg:ident, $Arg:ident)),* }
+        $verify:ident, $verify_or_delegate:ident { $(($n:tt, $arg:ident, $Arg:ident)),* }
     ) => {
+        #[allow(clippy::too_many_arguments)]
         pub fn $verify<$($Arg: DebugOnStable,)* Res>(
             &mut self, method_data: MethodData$(, $arg: $Arg)*
         ) -> impl FnOnce() -> Res {
+            match self.$verify_or_delegate(method_data, false$(, $arg)*) {
+                Ok(action) => action,
+                Err(_) => unreachable!(),
+            }
+        }
+
+        /// Same as `verifyN`, but when `delegating` is true and call doesn't
+        /// match any expectation, arguments are returned back, so that mock
+        /// can forward call to real implementation.
+        #[allow(clippy::type_complexity, clippy::too_many_arguments, clippy::result_unit_err)]
+        pub fn $verify_or_delegate<$($Arg: DebugOnStable,)* Res>(
+            &mut self, method_data: MethodData, delegating: bool$(, $arg: $Arg)*
+        ) -> Result<impl FnOnce() -> Res, ($($Arg,)*)> {
             let args = Box::new(($($arg,)*));
             let args_ptr: *const u8 = ::std::boxed::Box::into_raw(args) as *const u8;
             fn destroy<$($Arg,)*>(args_to_destroy: *const u8) {
-                unsafe { Box::from_raw(args_to_destroy as *mut ($($Arg,)*)) };
+                drop(unsafe { Box::from_raw(args_to_destroy as *mut ($($Arg,)*)) });
             }
             fn format_args<$($Arg: DebugOnStable,)*>(args_ptr: *const u8) -> String {
                 let __args_ref: &($($Arg,)*) = unsafe { &*(args_ptr as *const ($($Arg,)*)) };
@@ -744,22 +1736,63 @@ macro_rules! define_verify {
                 destroy: destroy::<$($Arg,)*>,
                 format_args: format_args::<$($Arg,)*>,
             };
-            let action = self.verify(call);
-            move || {
-                let result_ptr: *mut u8 = action.satisfy();
-                let result: Box<Res> = unsafe { Box::from_raw(result_ptr as *mut Res) };
-                *result
+            match self.verify(call, delegating) {
+                Ok(action) => Ok(move || {
+                    let result_ptr: *mut u8 = action.satisfy();
+                    let result: Box<Res> = unsafe { Box::from_raw(result_ptr as *mut Res) };
+                    *result
+                }),
+                Err(mut call) => {
+                    let args = unsafe { Box::from_raw(call.take_args() as *mut ($($Arg,)*)) };
+                    Err(*args)
+                }
             }
         }
     }
 }
 
 impl ScenarioInternals {
-    define_verify!(verify0 { });
-    define_verify!(verify1 { (0, arg0, Arg0) });
-    define_verify!(verify2 { (0, arg0, Arg0), (1, arg1, Arg1) });
-    define_verify!(verify3 { (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2) });
-    define_verify!(verify4 { (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3) });
+    define_verify!(verify0, verify_or_delegate0 { });
+    define_verify!(verify1, verify_or_delegate1 { (0, arg0, Arg0) });
+    define_verify!(verify2, verify_or_delegate2 { (0, arg0, Arg0), (1, arg1, Arg1) });
+    define_verify!(verify3, verify_or_delegate3 { (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2) });
+    define_verify!(verify4, verify_or_delegate4 { (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3) });
+    define_verify!(verify5, verify_or_delegate5 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4)
+    });
+    define_verify!(verify6, verify_or_delegate6 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5)
+    });
+    define_verify!(verify7, verify_or_delegate7 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6)
+    });
+    define_verify!(verify8, verify_or_delegate8 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7)
+    });
+    define_verify!(verify9, verify_or_delegate9 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+        (8, arg8, Arg8)
+    });
+    define_verify!(verify10, verify_or_delegate10 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+        (8, arg8, Arg8), (9, arg9, Arg9)
+    });
+    define_verify!(verify11, verify_or_delegate11 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+        (8, arg8, Arg8), (9, arg9, Arg9), (10, arg10, Arg10)
+    });
+    define_verify!(verify12, verify_or_delegate12 {
+        (0, arg0, Arg0), (1, arg1, Arg1), (2, arg2, Arg2), (3, arg3, Arg3),
+        (4, arg4, Arg4), (5, arg5, Arg5), (6, arg6, Arg6), (7, arg7, Arg7),
+        (8, arg8, Arg8), (9, arg9, Arg9), (10, arg10, Arg10), (11, arg11, Arg11)
+    });
 
     /// Verify call performed on mock object
     /// Returns closure which returns result upon call.
@@ -767,135 +1800,245 @@ impl ScenarioInternals {
     /// use user-provided closure as action, and that closure may want to
     /// use scenario object to create mocks or establish expectations, so
     /// we need to release scenario borrow before calling expectation action.
-    fn verify(&mut self, call: Call) -> Box<dyn Satisfy> {
-        for expectation in self.expectations.iter_mut().rev() {
-            if expectation.call_match().matches(&call) {
-                let mock_name = self
-                    .mock_names
-                    .get(&call.method_data.mock_id)
-                    .unwrap()
-                    .clone();
-                return expectation.satisfy(call, &mock_name);
+    ///
+    /// When `delegating` is true and call doesn't match any expectation,
+    /// call is returned back to be forwarded to real implementation.
+    fn verify(&mut self, call: Call, delegating: bool) -> Result<Box<dyn Satisfy>, Call> {
+        if self.record_calls {
+            self.record_call(&call);
+        }
+
+        if self.recent_calls_limit > 0 {
+            if self.recent_calls.len() > self.recent_calls_limit {
+                self.recent_calls.pop_front();
             }
+            let mock_name = self.get_mock_name(call.method_data.mock_id).to_owned();
+            self.recent_calls.push_back(ReceivedCall {
+                mock_name,
+                method_name: call.method_data.method_name.to_owned(),
+                args: (call.format_args)(call.args_ptr),
+                location: call.method_data.caller.map(SourceLocation::from),
+            });
         }
 
-        // No expectations exactly matching call are found. However this may be
-        // because of unexpected argument values. So check active expectations
-        // with matching target (i.e. mock and method) and validate arguments.
-        let mock_name = self.mock_names.get(&call.method_data.mock_id).unwrap();
+        let retire_on_saturation = self.retire_on_saturation;
+        let matched = self
+            .expectations
+            .iter()
+            .rposition(|e| !(retire_on_saturation && e.is_exhausted()) && e.matches(&call));
+
+        // Default action is used when there is no matching expectation or
+        // it is already exhausted and would fail anyway. Real implementation
+        // of partial mock is used only when there is no matching expectation
+        // at all, calls to exhausted expectations fail as usual.
+        let use_default = matched.map_or(true, |index| self.expectations[index].is_exhausted());
+        let default_action = if use_default {
+            self.default_actions.iter_mut().rev().find(|a| a.matches(&call))
+        } else {
+            None
+        };
+        if matched.is_none() && default_action.is_none() && delegating {
+            if !self.record_calls {
+                self.record_call(&call);
+            }
+            return Err(call);
+        }
 
-        let mut msg = String::new();
-        msg.write_str("\n\n").unwrap();
-        write!(
-            &mut msg,
-            concat!(
-                colored!(red: "error:"),
-                " ",
-                colored!(bold: "unexpected call to `{}.{}({})`\n\n")
-            ),
-            mock_name,
-            call.method_data.method_name,
-            (call.format_args)(call.args_ptr)
-        )
-        .unwrap();
-
-        if self.expectations.is_empty() {
-            msg.push_str("no call are expected");
-            panic!("{}", msg);
-        }
-
-        let mut target_first_match = true;
-        for expectation in self.expectations.iter().rev() {
-            if !expectation.is_satisfied() && expectation.call_match().matches_method(&call) {
-                if target_first_match {
-                    write!(
-                        &mut msg,
-                        concat!(
-                            colored!(green: "note: "),
-                            "here are active expectations for {}.{}\n"
-                        ),
-                        mock_name, call.method_data.method_name
-                    )
-                    .unwrap();
-                    target_first_match = false;
+        let expectation = match (default_action, matched) {
+            (Some(action), _) => Some(action),
+            (None, Some(index)) => Some(&mut self.expectations[index]),
+            (None, None) => None,
+        };
+        if let Some(expectation) = expectation {
+            let mock_name = self
+                .mock_names
+                .get(&call.method_data.mock_id)
+                .unwrap()
+                .clone();
+            match expectation.check_call_count(&mock_name) {
+                Ok(()) => {
+                    expectation.call_match().capture(&call);
+                    return Ok(expectation.satisfy(call, &mock_name));
+                }
+                Err(mut failure) => {
+                    failure.call_location = call.method_data.caller.map(SourceLocation::from);
+                    return Ok(self.fail(Failure::WrongCallCount(failure), &call));
                 }
+            }
+        }
 
-                write!(
-                    &mut msg,
-                    "\n  expectation `{}.{}`\n",
-                    mock_name,
-                    expectation.describe()
-                )
-                .unwrap();
-                for (index, res) in expectation.call_match().validate(&call).iter().enumerate() {
-                    match *res {
-                        Err(ref err) => write!(
-                            &mut msg,
-                            concat!("    arg #{}: ", colored!(bold: "{}"), "\n"),
-                            index, err
-                        )
-                        .unwrap(),
-                        Ok(()) => (),
-                    }
+        let mock_name = self.mock_names.get(&call.method_data.mock_id).unwrap();
+        let mode = self.mock_modes.get(&call.method_data.mock_id).cloned().unwrap_or(MockMode::Strict);
+        if mode != MockMode::Strict {
+            if let Some(default_result) = call.method_data.default_result {
+                if mode == MockMode::Naggy {
+                    self.warnings.push(format!(
+                        "{} uninteresting call to `{}.{}({})` on naggy mock, default value is returned",
+                        Style::Orange.paint("warning:", self.colors.enabled()),
+                        mock_name,
+                        call.method_data.method_name,
+                        (call.format_args)(call.args_ptr)
+                    ));
                 }
+                return Ok(Box::new(SatisfyDefault(default_result)));
+            }
+        }
+
+        let failure = self.unexpected_call(&call, mode);
+        Ok(self.fail(Failure::UnexpectedCall(failure), &call))
+    }
+
+    /// Reports failed call: panics or, when failures are collected, remembers
+    /// failure and returns default result if possible.
+    fn fail(&mut self, failure: Failure, call: &Call) -> Box<dyn Satisfy> {
+        let message = failure.to_string_colored(self.colors.enabled());
+        if self.collect_failures {
+            self.failures.push(failure);
+            if let Some(default_result) = call.method_data.default_result {
+                return Box::new(SatisfyDefault(default_result));
+            }
+        }
+        panic!("{}", message);
+    }
+
+    fn unexpected_call(&self, call: &Call, mode: MockMode) -> UnexpectedCall {
+        let mock_name = self.mock_names.get(&call.method_data.mock_id).unwrap();
+        let mut failure = UnexpectedCall {
+            mock_name: mock_name.to_string(),
+            method_name: call.method_data.method_name.to_owned(),
+            args: (call.format_args)(call.args_ptr),
+            call_location: call.method_data.caller.map(SourceLocation::from),
+            // Last recent call is current one.
+            recent_calls: self.recent_calls.iter().take(self.recent_calls.len().saturating_sub(1)).cloned().collect(),
+            not_strict: mode != MockMode::Strict,
+            no_expectations: self.expectations.is_empty(),
+            out_of_order: Vec::new(),
+            candidates: Vec::new(),
+            other_mocks: Vec::new(),
+        };
 
-                write!(&mut msg, "\n").unwrap();
+        for expectation in self.expectations.iter().rev() {
+            if let Some(prerequisite) = expectation.unsatisfied_prerequisite(call) {
+                failure.out_of_order.push(OutOfOrder {
+                    expectation: expectation.describe(),
+                    location: expectation.call_match().location().map(SourceLocation::from),
+                    prerequisite_mock_name: self.get_mock_name(prerequisite.mock_id).to_owned(),
+                    prerequisite: prerequisite.description,
+                    prerequisite_location: prerequisite.location.map(SourceLocation::from),
+                    sequence: prerequisite.sequence,
+                });
             }
         }
 
-        if target_first_match {
-            write!(
-                &mut msg,
-                concat!(
-                    colored!(green: "note: "),
-                    "there are no active expectations for {}.{}\n"
-                ),
-                mock_name, call.method_data.method_name
-            )
-            .unwrap();
+        // No expectations exactly matching call are found. However this may be
+        // because of unexpected argument values. So check active expectations
+        // with matching target (i.e. mock and method) and validate arguments.
+        for expectation in self.expectations.iter().rev() {
+            if !expectation.is_satisfied() && expectation.call_match().matches_method(call) {
+                let matchers = expectation.call_match().describe_args();
+                let args = expectation
+                    .call_match()
+                    .validate(call)
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(index, res)| {
+                        res.err().map(|error| ArgMismatch {
+                            index,
+                            matcher: matchers.get(index).cloned().unwrap_or_default(),
+                            error,
+                        })
+                    })
+                    .collect();
+                failure.candidates.push(ArgsMismatch {
+                    expectation: expectation.describe(),
+                    location: expectation.call_match().location().map(SourceLocation::from),
+                    args,
+                });
+            }
         }
 
-        let mut method_first_match = true;
         for expectation in self.expectations.iter().rev() {
             if !expectation.is_satisfied()
-                && !expectation.call_match().matches_target(&call)
-                && expectation.call_match().matches_method(&call)
-                && expectation.call_match().matches_args(&call)
+                && !expectation.call_match().matches_target(call)
+                && expectation.call_match().matches_method(call)
+                && expectation.call_match().matches_args(call)
             {
-                if method_first_match {
-                    msg.push_str(concat!(
-                        colored!(green: "note: "),
-                        "there are matching expectations for another mock objects\n"
-                    ));
-                    method_first_match = false;
-                }
-
-                let other_mock_id = &expectation.call_match().get_mock_id();
-                let other_mock_name = self.mock_names.get(other_mock_id).unwrap();
-                write!(
-                    &mut msg,
-                    concat!("\n  expectation `", colored!(bold: "{}"), ".{}`\n"),
-                    other_mock_name,
-                    expectation.describe()
-                )
-                .unwrap();
+                failure.other_mocks.push(OtherMockExpectation {
+                    mock_name: self.get_mock_name(expectation.call_match().get_mock_id()).to_owned(),
+                    expectation: expectation.describe(),
+                    location: expectation.call_match().location().map(SourceLocation::from),
+                });
             }
         }
 
-        msg.push('\n');
-        panic!("{}", msg);
+        failure
     }
 
-    pub fn get_mock_name(&self, mock_id: usize) -> &str {
-        self.mock_names.get(&mock_id).unwrap()
+    fn record_call(&mut self, call: &Call) {
+        self.recorded_calls.push(CallRecord {
+            method_data: call.method_data.clone(),
+            args_debug: (call.format_args)(call.args_ptr),
+            args: call.method_data.record_args.map(|recorder| (recorder.save)(call.args_ptr)),
+        });
     }
-}
 
-fn format_ordinal(n: u32) -> String {
-    match n % 10 {
-        1 => format!("{}st", n),
-        2 => format!("{}nd", n),
-        3 => format!("{}rd", n),
-        _ => format!("{}th", n),
+    /// Returns recorded calls matching given call match.
+    fn matching_calls<'a>(&'a self, call_match: &'a dyn CallMatch) -> impl Iterator<Item = &'a CallRecord> + 'a {
+        fn no_destroy(_: *const u8) {}
+        fn no_format(_: *const u8) -> String {
+            String::new()
+        }
+
+        if !self.record_calls && !self.partial_mocks.contains(&call_match.get_mock_id()) {
+            panic!("calls are not recorded, enable recording with `Scenario::record_calls`");
+        }
+        self.recorded_calls.iter().filter(move |record| {
+            let mut call = Call {
+                method_data: record.method_data.clone(),
+                args_ptr: std::ptr::null(),
+                destroy: no_destroy,
+                format_args: no_format,
+            };
+            if !call_match.matches_target(&call) || !call_match.matches_method(&call) {
+                return false;
+            }
+            // Calls with unrecorded arguments match only when arguments aren't
+            // needed for that, see `warn_unrecorded_args`.
+            record
+                .with_args(|args_ptr| {
+                    call.args_ptr = args_ptr;
+                    call_match.matches_args(&call)
+                })
+                .unwrap_or_else(|| call_match.matches_any_args())
+        })
+    }
+
+    /// Adds warning if given call match needs arguments of recorded calls
+    /// which aren't recorded, such calls are not counted as matching.
+    fn warn_unrecorded_args(&mut self, call_match: &dyn CallMatch) {
+        if call_match.matches_any_args() {
+            return;
+        }
+        let unrecorded = self.recorded_calls.iter().find(|record| {
+            call_match.get_mock_id() == record.method_data.mock_id
+                && call_match.get_mock_type_id() == record.method_data.mock_type_id
+                && call_match.get_method_name() == record.method_data.method_name
+                && record.args.is_none()
+        });
+        if let Some(record) = unrecorded {
+            let warning = format!(
+                "{} arguments of `{}.{}` calls are not recorded, so they don't match `{}`, \
+                 list method in `record_args` option of `#[mocked]` to record them",
+                Style::Orange.paint("warning:", self.colors.enabled()),
+                self.get_mock_name(record.method_data.mock_id),
+                record.method_data.method_name,
+                call_match.describe()
+            );
+            self.warnings.push(warning);
+        }
+    }
+
+    pub fn get_mock_name(&self, mock_id: usize) -> &str {
+        self.mock_names.get(&mock_id).unwrap()
     }
 }
@@ -0,0 +1,115 @@
+use crate::shared::{lock, Lock, Shared};
+use crate::MatchArg;
+
+/// Matcher which stores arguments of matched calls, so that they
+/// may be inspected after calls are made.
+///
+/// Captor matches any value, use `matching` to capture only values
+/// matched by another matcher. Captor is a handle, its clones and matchers
+/// created with `matching` share captured values.
+///
+/// Reference arguments are captured by converting them with `ToOwned`,
+/// so `Captor<str>` captures `&str` arguments as `String`s.
+pub struct Captor<T: ?Sized + ToOwned> {
+    values: Shared<Lock<Vec<T::Owned>>>,
+}
+
+impl<T: ?Sized + ToOwned> Captor<T> {
+    pub fn new() -> Self {
+        Captor { values: Shared::new(Lock::new(Vec::new())) }
+    }
+
+    /// Creates matcher which captures values matched by given matcher.
+    pub fn matching<M>(&self, matcher: M) -> CaptorMatchArg<T, M> {
+        CaptorMatchArg { captor: self.clone(), matcher }
+    }
+
+    /// Returns all captured values in order of calls.
+    pub fn values(&self) -> Vec<T::Owned>
+    where
+        T::Owned: Clone,
+    {
+        lock(&self.values).clone()
+    }
+
+    /// Returns value captured last.
+    pub fn last(&self) -> Option<T::Owned>
+    where
+        T::Owned: Clone,
+    {
+        lock(&self.values).last().cloned()
+    }
+
+    fn push(&self, value: T::Owned) {
+        lock(&self.values).push(value);
+    }
+}
+
+impl<T: ?Sized + ToOwned> Clone for Captor<T> {
+    fn clone(&self) -> Self {
+        Captor { values: self.values.clone() }
+    }
+}
+
+impl<T: ?Sized + ToOwned> Default for Captor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ToOwned> MatchArg<T> for Captor<T> {
+    fn matches(&self, _: &T) -> Result<(), String> {
+        Ok(())
+    }
+    fn describe(&self) -> String {
+        "captor(_)".to_owned()
+    }
+    fn capture(&self, arg: &T) {
+        self.push(arg.to_owned());
+    }
+}
+
+impl<'a, T: ?Sized + ToOwned> MatchArg<&'a T> for Captor<T> {
+    fn matches(&self, _: &&'a T) -> Result<(), String> {
+        Ok(())
+    }
+    fn describe(&self) -> String {
+        "captor(_)".to_owned()
+    }
+    fn capture(&self, arg: &&'a T) {
+        self.push((*arg).to_owned());
+    }
+}
+
+/// Matcher which captures values matched by another matcher,
+/// see `Captor::matching`.
+pub struct CaptorMatchArg<T: ?Sized + ToOwned, M> {
+    captor: Captor<T>,
+    matcher: M,
+}
+
+impl<T: ToOwned, M: MatchArg<T>> MatchArg<T> for CaptorMatchArg<T, M> {
+    fn matches(&self, arg: &T) -> Result<(), String> {
+        self.matcher.matches(arg)
+    }
+    fn describe(&self) -> String {
+        format!("captor({})", self.matcher.describe())
+    }
+    fn capture(&self, arg: &T) {
+        self.matcher.capture(arg);
+        self.captor.push(arg.to_owned());
+    }
+}
+
+impl<'a, T: ?Sized + ToOwned, M: MatchArg<&'a T>> MatchArg<&'a T> for CaptorMatchArg<T, M> {
+    fn matches(&self, arg: &&'a T) -> Result<(), String> {
+        self.matcher.matches(arg)
+    }
+    fn describe(&self) -> String {
+        format!("captor({})", self.matcher.describe())
+    }
+    fn capture(&self, arg: &&'a T) {
+        self.matcher.capture(arg);
+        self.captor.push((*arg).to_owned());
+    }
+}
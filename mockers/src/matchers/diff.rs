@@ -0,0 +1,72 @@
+//! Line diff of pretty-printed values used in mismatch messages.
+
+use std::fmt::{Debug, Write};
+
+/// Diff isn't computed for values with more lines than this,
+/// because its cost is quadratic.
+const MAX_LINES: usize = 1000;
+
+/// Formats message for argument not equal to expected value.
+///
+/// When both values are printed on several lines by `{:#?}`,
+/// line diff of these representations is added to message.
+pub(crate) fn not_equal<A: Debug + ?Sized, E: Debug + ?Sized>(actual: &A, expected: &E) -> String {
+    let mut msg = format!("{:?} is not equal to {:?}", actual, expected);
+    let actual = format!("{:#?}", actual);
+    let expected = format!("{:#?}", expected);
+    if actual.contains('\n') && expected.contains('\n') {
+        let actual: Vec<&str> = actual.lines().collect();
+        let expected: Vec<&str> = expected.lines().collect();
+        if actual.len() <= MAX_LINES && expected.len() <= MAX_LINES {
+            msg.push_str("\ndiff < expected / > actual:\n");
+            write_diff(&mut msg, &expected, &actual);
+            msg.truncate(msg.trim_end().len());
+        }
+    }
+    msg
+}
+
+/// Writes line diff based on longest common subsequence. Within each hunk
+/// expected lines go first, then actual ones, as header says.
+fn write_diff(out: &mut String, old: &[&str], new: &[&str]) {
+    // lcs[i][j] is length of longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Changed lines of current hunk, written when common line or end is reached.
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let flush = |out: &mut String, removed: &mut Vec<&str>, added: &mut Vec<&str>| {
+        for line in removed.drain(..) {
+            writeln!(out, "< {}", line).unwrap();
+        }
+        for line in added.drain(..) {
+            writeln!(out, "> {}", line).unwrap();
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            flush(out, &mut removed, &mut added);
+            writeln!(out, "  {}", old[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if j == new.len() || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            removed.push(old[i]);
+            i += 1;
+        } else {
+            added.push(new[j]);
+            j += 1;
+        }
+    }
+    flush(out, &mut removed, &mut added);
+}
@@ -43,6 +43,9 @@ impl<T: Debug, M: MatchArg<T>, F: Fn() -> String> MatchArg<T> for WithDescriptio
     fn describe(&self) -> String {
         self.description()
     }
+    fn capture(&self, arg: &T) {
+        self.matcher.capture(arg)
+    }
 }
 
 pub struct WithMessageFn<T: Debug, M: MatchArg<T>, F: Fn(&T) -> String> {
@@ -69,4 +72,7 @@ impl<T: Debug, M: MatchArg<T>, F: Fn(&T) -> String> MatchArg<T> for WithMessageF
     fn describe(&self) -> String {
         self.matcher.describe()
     }
+    fn capture(&self, arg: &T) {
+        self.matcher.capture(arg)
+    }
 }
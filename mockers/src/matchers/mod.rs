@@ -8,10 +8,13 @@ use std::collections::Bound;
 use std::fmt::Write;
 use std::ops::RangeBounds;
 
+pub use self::captor::*;
 pub use self::ext::*;
 pub use self::option::*;
 pub use self::result::*;
 
+mod captor;
+mod diff;
 mod ext;
 mod option;
 mod result;
@@ -22,7 +25,7 @@ impl<T: std::fmt::Debug, S: std::fmt::Debug + PartialEq<T>> MatchArg<T> for S {
         if self == arg {
             Ok(())
         } else {
-            Err(format!("{:?} is not equal to {:?}", arg, self))
+            Err(diff::not_equal(arg, self))
         }
     }
 
@@ -43,6 +46,10 @@ impl<T, M: MatchArg<T>> MatchArg<&T> for MatchRef<T, M> {
     fn describe(&self) -> String {
         self.inner.describe()
     }
+
+    fn capture(&self, arg: &&T) {
+        self.inner.capture(*arg)
+    }
 }
 
 pub fn by_ref<T, M: MatchArg<T>>(inner: M) -> MatchRef<T, M> {
@@ -63,6 +70,10 @@ impl<T> MatchArg<T> for MatchAny {
     fn describe(&self) -> String {
         "_".to_owned()
     }
+
+    fn matches_any(&self) -> bool {
+        true
+    }
 }
 /// Matches any value.
 pub const ANY: MatchAny = MatchAny;
@@ -76,6 +87,10 @@ impl<T> MatchArg<T> for MatchAnyT<T> {
     fn describe(&self) -> String {
         "_".to_owned()
     }
+
+    fn matches_any(&self) -> bool {
+        true
+    }
 }
 
 pub fn any<T>() -> MatchAnyT<T> {
@@ -141,11 +156,30 @@ macro_rules! simple_matcher {
 
 simple_matcher!(lt, LtMatchArg,  <, "not less than", PartialOrd);
 simple_matcher!(le, LeMatchArg, <=, "not less than or equal to", PartialOrd);
-simple_matcher!(eq, EqMatchArg, ==, "not equal to", PartialEq);
 simple_matcher!(ne, NeMatchArg, !=, "equal to", PartialEq);
 simple_matcher!(ge, GeMatchArg, >=, "not greater than or equal to", PartialOrd);
 simple_matcher!(gt, GtMatchArg,  >, "not greater than", PartialOrd);
 
+/// Not defined with `simple_matcher!`, because mismatch message
+/// includes diff of structured values.
+pub struct EqMatchArg<T>(T);
+impl<T: PartialEq + Debug> MatchArg<T> for EqMatchArg<T> {
+    fn matches(&self, arg: &T) -> Result<(), String> {
+        if *arg == self.0 {
+            Ok(())
+        } else {
+            Err(diff::not_equal(arg, &self.0))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("eq({:?})", self.0)
+    }
+}
+pub fn eq<T: PartialEq + Debug>(than: T) -> EqMatchArg<T> {
+    EqMatchArg(than)
+}
+
 pub struct RangeMatchArg<T: Ord + Debug, R: RangeBounds<T>> {
     range: R,
     _phantom: PhantomData<T>,
@@ -235,6 +269,11 @@ impl<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for AndMatchArg<T,
     fn describe(&self) -> String {
         format!("and({}, {})", self.0.describe(), self.1.describe())
     }
+
+    fn capture(&self, arg: &T) {
+        self.0.capture(arg);
+        self.1.capture(arg);
+    }
 }
 pub fn and<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>>(
     matcher0: M0,
@@ -258,6 +297,14 @@ impl<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>> MatchArg<T> for OrMatchArg<T, M
     fn describe(&self) -> String {
         format!("or({}, {})", self.0.describe(), self.1.describe())
     }
+
+    fn capture(&self, arg: &T) {
+        if self.0.matches(arg).is_ok() {
+            self.0.capture(arg);
+        } else {
+            self.1.capture(arg);
+        }
+    }
 }
 pub fn or<T: Debug, M0: MatchArg<T>, M1: MatchArg<T>>(
     matcher0: M0,
@@ -20,6 +20,11 @@ impl<T: Debug, M: MatchArg<T>> MatchArg<Option<T>> for MatchSome<T, M> {
     fn describe(&self) -> String {
         format!("some({})", self.0.describe())
     }
+    fn capture(&self, option: &Option<T>) {
+        if let Some(ref value) = *option {
+            self.0.capture(value);
+        }
+    }
 }
 pub fn some<T, M: MatchArg<T>>(m: M) -> MatchSome<T, M> {
     MatchSome(m, PhantomData)
@@ -14,6 +14,11 @@ impl<T: Debug, E: Debug, M: MatchArg<T>> MatchArg<Result<T, E>> for MatchOk<T, M
     fn describe(&self) -> String {
         format!("ok({})", self.0.describe())
     }
+    fn capture(&self, result: &Result<T, E>) {
+        if let Ok(ref value) = *result {
+            self.0.capture(value);
+        }
+    }
 }
 pub fn ok<T, M: MatchArg<T>>(m: M) -> MatchOk<T, M> {
     MatchOk(m, PhantomData)
@@ -30,6 +35,11 @@ impl<E: Debug, T: Debug, M: MatchArg<E>> MatchArg<Result<T, E>> for MatchErr<E,
     fn describe(&self) -> String {
         format!("err({})", self.0.describe())
     }
+    fn capture(&self, result: &Result<T, E>) {
+        if let Err(ref err) = *result {
+            self.0.capture(err);
+        }
+    }
 }
 pub fn err<E, M: MatchArg<E>>(m: M) -> MatchErr<E, M> {
     MatchErr(m, PhantomData)
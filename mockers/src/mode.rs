@@ -0,0 +1,60 @@
+//! Mock strictness modes.
+
+use std::marker::PhantomData;
+
+/// Defines how mock reacts to calls which don't match any expectation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MockMode {
+    /// Unexpected calls return default value.
+    Nice,
+    /// Unexpected calls return default value, warning about each such call
+    /// is printed when scenario is dropped.
+    Naggy,
+    /// Unexpected calls fail test. This is default mode.
+    Strict,
+}
+
+/// Type-erased factory of default method result, it returns pointer to
+/// boxed value.
+pub type DefaultResult = fn() -> *mut u8;
+
+fn boxed_default<T: Default>() -> *mut u8 {
+    Box::into_raw(Box::<T>::default()) as *mut u8
+}
+
+/// Helper used by generated code to get default result factory for method
+/// result type, when the type implements `Default`.
+///
+/// It relies on autoref-based specialization, so it works for concrete types only,
+/// for generic types result is always `None`:
+///
+/// ```rust,ignore
+/// use ::mockers::mode::{ViaDefault as _, NoDefault as _};
+/// let default_result = (&DefaultResultOf::<u32>::new()).default_result();
+/// ```
+pub struct DefaultResultOf<T>(PhantomData<fn() -> T>);
+
+impl<T> DefaultResultOf<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        DefaultResultOf(PhantomData)
+    }
+}
+
+pub trait ViaDefault {
+    fn default_result(&self) -> Option<DefaultResult>;
+}
+impl<T: Default> ViaDefault for DefaultResultOf<T> {
+    fn default_result(&self) -> Option<DefaultResult> {
+        Some(boxed_default::<T>)
+    }
+}
+
+pub trait NoDefault {
+    fn default_result(&self) -> Option<DefaultResult>;
+}
+impl<T> NoDefault for &DefaultResultOf<T> {
+    fn default_result(&self) -> Option<DefaultResult> {
+        None
+    }
+}
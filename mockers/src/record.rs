@@ -0,0 +1,93 @@
+//! Recording of calls made on mocks, used to verify calls after they are made.
+
+use std::any::Any;
+
+use crate::MethodData;
+
+/// Type-erased copy of call arguments saved by recorder.
+#[cfg(not(feature = "sync"))]
+pub type SavedArgs = Box<dyn Any>;
+/// Type-erased copy of call arguments saved by recorder.
+#[cfg(feature = "sync")]
+pub type SavedArgs = Box<dyn Any + Send>;
+
+/// Functions saving call arguments and restoring them later, they are
+/// generated for methods listed in `record_args` option of `#[mocked]`.
+///
+/// Arguments which are references are saved as owned values using
+/// `ToOwned`, other arguments are cloned:
+///
+/// ```rust,ignore
+/// // fn log(&self, level: u32, message: &str)
+/// ArgsRecorder {
+///     save: |args_ptr| {
+///         let args = unsafe { &*(args_ptr as *const (u32, &str)) };
+///         Box::new((args.0.clone(), <str as ToOwned>::to_owned(&*args.1)))
+///     },
+///     restore: |saved, f| {
+///         let saved = saved.downcast_ref::<(u32, <str as ToOwned>::Owned)>().unwrap();
+///         let args = (saved.0.clone(), Borrow::<str>::borrow(&saved.1));
+///         f(&args as *const _ as *const u8)
+///     },
+///     saved_type: std::any::type_name::<(u32, <str as ToOwned>::Owned)>,
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct ArgsRecorder {
+    /// Saves arguments tuple given by pointer.
+    pub save: fn(*const u8) -> SavedArgs,
+    /// Restores arguments tuple from saved copy and passes pointer to it
+    /// to given function. Restored references point into saved copy, so
+    /// they must not outlive the call.
+    pub restore: fn(&SavedArgs, &mut dyn FnMut(*const u8)),
+    /// Returns name of saved arguments tuple type.
+    pub saved_type: fn() -> &'static str,
+}
+
+/// Call recorded by scenario.
+pub struct RecordedCall<Args> {
+    /// Name of mock method was called on.
+    pub mock_name: String,
+    /// Name of called method.
+    pub method_name: &'static str,
+    /// Arguments formatted with `Debug`.
+    pub args_debug: String,
+    /// Arguments as they are saved: references are replaced with owned
+    /// values, e.g. `(u32, String)` for `(u32, &str)`. Available when
+    /// arguments are recorded.
+    pub args: Option<Args>,
+}
+
+/// Call as it is stored in scenario.
+pub(crate) struct CallRecord {
+    pub method_data: MethodData,
+    pub args_debug: String,
+    pub args: Option<SavedArgs>,
+}
+
+impl CallRecord {
+    /// Restores recorded arguments and passes pointer to them, suitable for
+    /// `Call::args_ptr`, to given function. Returns `None` if arguments
+    /// aren't recorded.
+    pub fn with_args<R>(&self, f: impl FnOnce(*const u8) -> R) -> Option<R> {
+        let recorder = self.method_data.record_args?;
+        let args = self.args.as_ref()?;
+        let mut f = Some(f);
+        let mut result = None;
+        (recorder.restore)(args, &mut |args_ptr| result = f.take().map(|f| f(args_ptr)));
+        result
+    }
+
+    /// Returns copy of saved arguments, `None` if arguments aren't recorded.
+    /// Returns name of saved arguments type if it isn't `Args`.
+    pub fn saved_args<Args: Clone + 'static>(&self) -> Result<Option<Args>, &'static str> {
+        let (recorder, args) = match (self.method_data.record_args, self.args.as_ref()) {
+            (Some(recorder), Some(args)) => (recorder, args),
+            _ => return Ok(None),
+        };
+        match args.downcast_ref::<Args>() {
+            Some(args) => Ok(Some(args.clone())),
+            None => Err((recorder.saved_type)()),
+        }
+    }
+}
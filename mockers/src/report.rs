@@ -0,0 +1,409 @@
+//! Failures found by scenario, see `Scenario::verify`.
+//!
+//! Failures are structured, so that tools may process them. With `json`
+//! feature enabled they may be serialized to JSON using `to_json`.
+
+use std::fmt;
+use std::panic::Location;
+
+use crate::colors::{ColorChoice, Style};
+
+#[cfg(feature = "json")]
+use serde::Serialize;
+
+/// Single problem found by scenario.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize), serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Failure {
+    /// Call which doesn't match any expectation.
+    UnexpectedCall(UnexpectedCall),
+    /// Call made after expectation reached expected number of calls.
+    WrongCallCount(WrongCallCount),
+    /// Expectation which is not satisfied when scenario is verified.
+    UnsatisfiedExpectation(UnsatisfiedExpectation),
+}
+
+/// Call which doesn't match any expectation.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct UnexpectedCall {
+    pub mock_name: String,
+    pub method_name: String,
+    /// Debug representation of arguments, separated by commas.
+    pub args: String,
+    /// Place where call is made.
+    pub call_location: Option<SourceLocation>,
+    /// Calls received by scenario before this one, see `Scenario::show_recent_calls`.
+    pub recent_calls: Vec<ReceivedCall>,
+    /// Whether mock is nice or naggy, but default result can't be created.
+    pub not_strict: bool,
+    /// Whether there are no expectations in scenario at all.
+    pub no_expectations: bool,
+    /// Expectations matching call, but waiting for other calls first.
+    pub out_of_order: Vec<OutOfOrder>,
+    /// Active expectations for the same method of the same mock,
+    /// along with arguments they don't match.
+    pub candidates: Vec<ArgsMismatch>,
+    /// Active expectations matching call, but set on other mocks.
+    pub other_mocks: Vec<OtherMockExpectation>,
+}
+
+/// Place in source code where expectation is declared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: u32,
+}
+
+impl<'a> From<&'a Location<'a>> for SourceLocation {
+    fn from(location: &'a Location<'a>) -> Self {
+        SourceLocation { file: location.file().to_owned(), line: location.line() }
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// Writes line with location of expectation, if it is known.
+fn write_location(f: &mut fmt::Formatter, indent: &str, what: &str, location: &Option<SourceLocation>) -> fmt::Result {
+    match *location {
+        Some(ref location) => writeln!(f, "{}{} at {}", indent, what, location),
+        None => Ok(()),
+    }
+}
+
+/// Call received by scenario.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct ReceivedCall {
+    pub mock_name: String,
+    pub method_name: String,
+    pub args: String,
+    pub location: Option<SourceLocation>,
+}
+
+/// Expectation which matches call, but whose prerequisite isn't satisfied.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct OutOfOrder {
+    pub expectation: String,
+    pub location: Option<SourceLocation>,
+    pub prerequisite_mock_name: String,
+    pub prerequisite: String,
+    pub prerequisite_location: Option<SourceLocation>,
+    /// Name of sequence prerequisite belongs to, if it is named.
+    pub sequence: Option<String>,
+}
+
+/// Expectation for called method along with arguments it doesn't match.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct ArgsMismatch {
+    pub expectation: String,
+    pub location: Option<SourceLocation>,
+    pub args: Vec<ArgMismatch>,
+}
+
+/// Argument which isn't matched by matcher.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct ArgMismatch {
+    pub index: usize,
+    /// Description of matcher.
+    pub matcher: String,
+    /// Explanation of mismatch given by matcher.
+    pub error: String,
+}
+
+/// Expectation set on another mock.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct OtherMockExpectation {
+    pub mock_name: String,
+    pub expectation: String,
+    pub location: Option<SourceLocation>,
+}
+
+/// Call made after expectation reached expected number of calls.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct WrongCallCount {
+    pub mock_name: String,
+    pub method_name: String,
+    /// Number of this call, starting from one.
+    pub count: u32,
+    pub violation: CallCountViolation,
+    /// Location of expectation which is already saturated.
+    pub location: Option<SourceLocation>,
+    /// Place where call is made.
+    pub call_location: Option<SourceLocation>,
+}
+
+/// Kind of expectation violated by too many calls.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize), serde(tag = "kind", rename_all = "snake_case"))]
+pub enum CallCountViolation {
+    /// Expectation created with `never()`.
+    NeverExpected,
+    /// Single-call expectation created with `and_return`, `and_call` and so on.
+    AlreadyCalled,
+    /// Expectation with cardinality, `expected` describes its upper bound.
+    TooMany { expected: String },
+}
+
+/// Expectation which is not satisfied when scenario is verified.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct UnsatisfiedExpectation {
+    pub mock_name: String,
+    pub expectation: String,
+    pub location: Option<SourceLocation>,
+}
+
+impl Failure {
+    /// Formats failure with or without ANSI colors.
+    pub(crate) fn fmt_colored(&self, f: &mut fmt::Formatter, colors: bool) -> fmt::Result {
+        match *self {
+            Failure::UnexpectedCall(ref failure) => failure.fmt_colored(f, colors),
+            Failure::WrongCallCount(ref failure) => fmt::Display::fmt(failure, f),
+            Failure::UnsatisfiedExpectation(ref failure) => fmt::Display::fmt(failure, f),
+        }
+    }
+
+    /// Formats failure to string using given color choice.
+    pub(crate) fn to_string_colored(&self, colors: bool) -> String {
+        struct Colored<'a>(&'a Failure, bool);
+        impl<'a> fmt::Display for Colored<'a> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt_colored(f, self.1)
+            }
+        }
+        Colored(self, colors).to_string()
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_colored(f, ColorChoice::Auto.enabled())
+    }
+}
+
+impl UnexpectedCall {
+    fn fmt_colored(&self, f: &mut fmt::Formatter, colors: bool) -> fmt::Result {
+        let note = Style::Green.paint("note: ", colors);
+
+        write!(
+            f,
+            "\n\n{} {}",
+            Style::Red.paint("error:", colors),
+            Style::Bold.paint(
+                format_args!("unexpected call to `{}.{}({})`\n\n", self.mock_name, self.method_name, self.args),
+                colors
+            )
+        )?;
+
+        if let Some(ref location) = self.call_location {
+            write!(f, "{}called at {}\n\n", note, location)?;
+        }
+
+        if !self.recent_calls.is_empty() {
+            writeln!(f, "{}calls received before this one:", note)?;
+            for call in &self.recent_calls {
+                write!(f, "  `{}.{}({})`", call.mock_name, call.method_name, call.args)?;
+                match call.location {
+                    Some(ref location) => writeln!(f, " at {}", location)?,
+                    None => writeln!(f)?,
+                }
+            }
+            f.write_str("\n")?;
+        }
+
+        if self.not_strict {
+            write!(f, "{}mock isn't strict, but method result type doesn't implement `Default`\n\n", note)?;
+        }
+
+        if self.no_expectations {
+            return f.write_str("no call are expected");
+        }
+
+        for out_of_order in &self.out_of_order {
+            let sequence = match out_of_order.sequence {
+                Some(ref name) => format!(" in sequence `{}`", name),
+                None => String::new(),
+            };
+            write!(
+                f,
+                concat!(
+                    "{}call is out of order, it matches `{}.{}`, but prerequisite\n",
+                    "  `{}.{}`{} is not satisfied yet\n"
+                ),
+                note,
+                self.mock_name,
+                out_of_order.expectation,
+                out_of_order.prerequisite_mock_name,
+                out_of_order.prerequisite,
+                sequence
+            )?;
+            write_location(f, "  ", "expectation is declared", &out_of_order.location)?;
+            write_location(f, "  ", "prerequisite is declared", &out_of_order.prerequisite_location)?;
+            f.write_str("\n")?;
+        }
+
+        if self.candidates.is_empty() {
+            writeln!(f, "{}there are no active expectations for {}.{}", note, self.mock_name, self.method_name)?;
+        } else {
+            writeln!(f, "{}here are active expectations for {}.{}", note, self.mock_name, self.method_name)?;
+            for candidate in &self.candidates {
+                writeln!(f, "\n  expectation `{}.{}`", self.mock_name, candidate.expectation)?;
+                write_location(f, "    ", "declared", &candidate.location)?;
+                for arg in &candidate.args {
+                    // Multi-line errors, like value diffs, are indented under argument.
+                    let error = arg.error.replace('\n', "\n      ");
+                    writeln!(f, "    arg #{}: {}", arg.index, Style::Bold.paint(error, colors))?;
+                }
+                f.write_str("\n")?;
+            }
+        }
+
+        if !self.other_mocks.is_empty() {
+            writeln!(f, "{}there are matching expectations for another mock objects", note)?;
+            for other in &self.other_mocks {
+                write!(
+                    f,
+                    "\n  expectation `{}.{}`\n",
+                    Style::Bold.paint(&other.mock_name, colors),
+                    other.expectation
+                )?;
+                write_location(f, "    ", "declared", &other.location)?;
+            }
+        }
+
+        f.write_str("\n")
+    }
+}
+
+impl fmt::Display for UnexpectedCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_colored(f, ColorChoice::Auto.enabled())
+    }
+}
+
+impl fmt::Display for WrongCallCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_violation(f)?;
+        if let Some(ref location) = self.location {
+            write!(f, "\n  expectation is declared at {}", location)?;
+        }
+        if let Some(ref location) = self.call_location {
+            write!(f, "\n  called at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl WrongCallCount {
+    fn fmt_violation(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.violation {
+            CallCountViolation::NeverExpected => {
+                write!(f, "{}.{} should never be called", self.mock_name, self.method_name)
+            }
+            CallCountViolation::AlreadyCalled => {
+                write!(f, "{}.{} was already called earlier", self.mock_name, self.method_name)
+            }
+            CallCountViolation::TooMany { ref expected } => write!(
+                f,
+                "{}.{} is called for the {} time, but expected to be {}",
+                self.mock_name,
+                self.method_name,
+                format_ordinal(self.count),
+                expected
+            ),
+        }
+    }
+}
+
+impl fmt::Display for UnsatisfiedExpectation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}.{}` is not satisfied", self.mock_name, self.expectation)?;
+        if let Some(ref location) = self.location {
+            write!(f, ", it is declared at {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+fn format_ordinal(n: u32) -> String {
+    match n % 10 {
+        1 => format!("{}st", n),
+        2 => format!("{}nd", n),
+        3 => format!("{}rd", n),
+        _ => format!("{}th", n),
+    }
+}
+
+/// All failures found by scenario.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize))]
+pub struct VerificationReport {
+    failures: Vec<Failure>,
+    /// Whether `Display` uses ANSI colors, chosen by scenario.
+    #[cfg_attr(feature = "json", serde(skip))]
+    colors: bool,
+}
+
+impl VerificationReport {
+    pub(crate) fn new(failures: Vec<Failure>, colors: bool) -> Self {
+        VerificationReport { failures, colors }
+    }
+
+    pub fn failures(&self) -> &[Failure] {
+        &self.failures
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Serializes report to JSON.
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("failed to serialize report")
+    }
+}
+
+#[cfg(feature = "json")]
+impl Failure {
+    /// Serializes failure to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("failed to serialize failure")
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut unsatisfied = Vec::new();
+        for failure in &self.failures {
+            match *failure {
+                Failure::UnsatisfiedExpectation(ref expectation) => unsatisfied.push(expectation),
+                _ => {
+                    failure.fmt_colored(f, self.colors)?;
+                    f.write_str("\n")?;
+                }
+            }
+        }
+        if !unsatisfied.is_empty() {
+            writeln!(f, "Some expectations are not satisfied:")?;
+            for expectation in unsatisfied {
+                writeln!(f, "`{}.{}`", expectation.mock_name, expectation.expectation)?;
+                write_location(f, "  ", "declared", &expectation.location)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for VerificationReport {}
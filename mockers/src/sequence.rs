@@ -0,0 +1,218 @@
+//! Ordering of expectations.
+//!
+//! Expectations may be put into sequences. Each expectation in sequence has
+//! previous one as prerequisite: it doesn't match calls until all prerequisites
+//! are satisfied. Expectation may belong to several sequences at once, so
+//! expectations form partial order.
+
+use std::panic::Location;
+
+use crate::report::WrongCallCount;
+use crate::shared::{lock, Lock, Shared};
+use crate::{Call, CallMatch, Expectation, IntoExpectations, Satisfy};
+
+type StateRef = Shared<Lock<State>>;
+
+/// State of sequenced expectation visible to expectations depending on it.
+struct State {
+    mock_id: usize,
+    description: String,
+    location: Option<&'static Location<'static>>,
+    satisfied: bool,
+    /// Retired expectation doesn't match calls anymore. Expectation is
+    /// retired when later expectation in sequence is matched.
+    retired: bool,
+    prerequisites: Vec<Prerequisite>,
+}
+
+struct Prerequisite {
+    sequence: Option<Shared<String>>,
+    state: StateRef,
+}
+
+/// Prerequisite expectation which isn't satisfied yet.
+pub struct UnsatisfiedPrerequisite {
+    /// ID of mock prerequisite expectation belongs to.
+    pub mock_id: usize,
+    /// Description of prerequisite expectation.
+    pub description: String,
+    /// Place where prerequisite expectation is declared.
+    pub location: Option<&'static Location<'static>>,
+    /// Name of sequence linking expectations.
+    pub sequence: Option<String>,
+}
+
+fn find_unsatisfied(prerequisites: &[Prerequisite]) -> Option<UnsatisfiedPrerequisite> {
+    for prerequisite in prerequisites {
+        let state = lock(&prerequisite.state);
+        if !state.satisfied {
+            return Some(UnsatisfiedPrerequisite {
+                mock_id: state.mock_id,
+                description: state.description.clone(),
+                location: state.location,
+                sequence: prerequisite.sequence.as_ref().map(|name| name.to_string()),
+            });
+        }
+        if let Some(unsatisfied) = find_unsatisfied(&state.prerequisites) {
+            return Some(unsatisfied);
+        }
+    }
+    None
+}
+
+fn retire(prerequisites: &[Prerequisite]) {
+    for prerequisite in prerequisites {
+        let mut state = lock(&prerequisite.state);
+        if !state.retired {
+            state.retired = true;
+            retire(&state.prerequisites);
+        }
+    }
+}
+
+struct SequenceState {
+    name: Option<Shared<String>>,
+    last: Option<StateRef>,
+    /// Expectations added with `Sequence::expect` and not yet passed to scenario.
+    pending: Vec<Box<dyn Expectation>>,
+}
+
+/// Sequence of expectations which must be satisfied in order.
+///
+/// Sequence is a handle, its clones refer to the same sequence.
+///
+/// Expectations are added to sequence either with `in_sequence` method
+/// or with `Scenario::in_sequence` scope. Legacy way of collecting
+/// expectations with `Sequence::expect` and then passing whole sequence
+/// to `Scenario::expect` is supported too.
+#[derive(Clone)]
+pub struct Sequence {
+    state: Shared<Lock<SequenceState>>,
+}
+
+impl Sequence {
+    pub fn new() -> Self {
+        Self::create(None)
+    }
+
+    /// Creates sequence with name which is used in error messages.
+    pub fn named(name: &str) -> Self {
+        Self::create(Some(Shared::new(name.to_string())))
+    }
+
+    fn create(name: Option<Shared<String>>) -> Self {
+        Sequence {
+            state: Shared::new(Lock::new(SequenceState {
+                name,
+                last: None,
+                pending: Vec::new(),
+            })),
+        }
+    }
+
+    /// Adds expectation to the end of sequence. Expectations are
+    /// registered in scenario when sequence is passed to `Scenario::expect`.
+    pub fn expect<E: Expectation + 'static>(&mut self, expectation: E) {
+        let expectation = SequencedExpectation::new(expectation).in_sequence(self);
+        lock(&self.state).pending.push(Box::new(expectation));
+    }
+
+    /// Makes given expectation state last one in sequence, returns
+    /// previous last one.
+    fn append(&self, state: &StateRef) -> Option<Prerequisite> {
+        let mut seq = lock(&self.state);
+        seq.last.replace(state.clone()).map(|last| Prerequisite {
+            sequence: seq.name.clone(),
+            state: last,
+        })
+    }
+}
+
+impl Default for Sequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IntoExpectations for Sequence {
+    fn into_expectations(self) -> Vec<Box<dyn Expectation>> {
+        std::mem::take(&mut lock(&self.state).pending)
+    }
+}
+
+/// Expectation which belongs to one or more sequences.
+pub struct SequencedExpectation {
+    inner: Box<dyn Expectation>,
+    state: StateRef,
+}
+
+impl SequencedExpectation {
+    pub fn new<E: Expectation + 'static>(expectation: E) -> Self {
+        Self::from_boxed(Box::new(expectation))
+    }
+
+    pub(crate) fn from_boxed(inner: Box<dyn Expectation>) -> Self {
+        let state = State {
+            mock_id: inner.call_match().get_mock_id(),
+            description: inner.describe(),
+            location: inner.call_match().location(),
+            satisfied: inner.is_satisfied(),
+            retired: false,
+            prerequisites: Vec::new(),
+        };
+        SequencedExpectation {
+            inner,
+            state: Shared::new(Lock::new(state)),
+        }
+    }
+
+    /// Adds expectation to the end of one more sequence.
+    pub fn in_sequence(self, sequence: &Sequence) -> Self {
+        if let Some(prerequisite) = sequence.append(&self.state) {
+            lock(&self.state).prerequisites.push(prerequisite);
+        }
+        self
+    }
+}
+
+impl Expectation for SequencedExpectation {
+    fn call_match(&self) -> &dyn CallMatch {
+        self.inner.call_match()
+    }
+    fn matches(&self, call: &Call) -> bool {
+        let state = lock(&self.state);
+        !state.retired
+            && self.inner.matches(call)
+            && find_unsatisfied(&state.prerequisites).is_none()
+    }
+    fn is_satisfied(&self) -> bool {
+        self.inner.is_satisfied()
+    }
+    fn is_exhausted(&self) -> bool {
+        self.inner.is_exhausted()
+    }
+    fn check_call_count(&self, mock_name: &str) -> Result<(), WrongCallCount> {
+        self.inner.check_call_count(mock_name)
+    }
+    fn satisfy(&mut self, call: Call, mock_name: &str) -> Box<dyn Satisfy> {
+        retire(&lock(&self.state).prerequisites);
+        let result = self.inner.satisfy(call, mock_name);
+        let mut state = lock(&self.state);
+        state.satisfied = self.inner.is_satisfied();
+        state.description = self.inner.describe();
+        result
+    }
+    fn describe(&self) -> String {
+        self.inner.describe()
+    }
+    fn unsatisfied_prerequisite(&self, call: &Call) -> Option<UnsatisfiedPrerequisite> {
+        if let Some(unsatisfied) = self.inner.unsatisfied_prerequisite(call) {
+            return Some(unsatisfied);
+        }
+        let state = lock(&self.state);
+        if state.retired || !self.inner.matches(call) {
+            return None;
+        }
+        find_unsatisfied(&state.prerequisites)
+    }
+}
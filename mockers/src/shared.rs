@@ -0,0 +1,46 @@
+//! Shared ownership primitives used to link scenario, mocks and handles together.
+//!
+//! By default scenario state is single-threaded and kept in `Rc<RefCell<..>>`.
+//! With `sync` feature enabled it is kept in `Arc<Mutex<..>>` instead, so that
+//! mocks may be sent to and called from other threads.
+//!
+//! Cargo unifies features, so once any crate in the build enables `sync`,
+//! all scenarios are thread-safe and require `Send` reactions and matchers.
+
+#[cfg(not(feature = "sync"))]
+pub use std::cell::{RefCell as Lock, RefMut as LockGuard};
+#[cfg(not(feature = "sync"))]
+pub use std::rc::{Rc as Shared, Weak as WeakShared};
+
+#[cfg(feature = "sync")]
+pub use std::sync::{Arc as Shared, Mutex as Lock, MutexGuard as LockGuard, Weak as WeakShared};
+
+/// Gets exclusive access to shared value.
+#[cfg(not(feature = "sync"))]
+pub fn lock<T: ?Sized>(lock: &Lock<T>) -> LockGuard<'_, T> {
+    lock.borrow_mut()
+}
+
+/// Gets exclusive access to shared value.
+///
+/// Lock poisoning is ignored: panic inside of mocked method is normal way
+/// to report test failure, it must not prevent scenario from reporting
+/// remaining expectations.
+#[cfg(feature = "sync")]
+pub fn lock<T: ?Sized>(lock: &Lock<T>) -> LockGuard<'_, T> {
+    lock.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(not(feature = "sync"))]
+/// This trait is implemented for all types by default, and is equivalent to `Send`
+/// when `sync` feature is enabled.
+pub trait MaybeSend {}
+#[cfg(not(feature = "sync"))]
+impl<T: ?Sized> MaybeSend for T {}
+
+#[cfg(feature = "sync")]
+/// This trait is implemented for all types by default, and is equivalent to `Send`
+/// when `sync` feature is enabled.
+pub trait MaybeSend: Send {}
+#[cfg(feature = "sync")]
+impl<T: ?Sized + Send> MaybeSend for T {}
@@ -1,42 +1,73 @@
 ///! Manual runtime type info for mocking generic methods
 
+use std::cell::Cell;
+
+use crate::colors::{ColorChoice, Style};
+use crate::shared::{lock, Lock};
+use crate::ScenarioInternals;
+
+thread_local! {
+    /// Colors chosen for scenario whose mock is evaluating type parameter IDs.
+    static COLORS: Cell<ColorChoice> = const { Cell::new(ColorChoice::Auto) };
+}
+
 pub trait TypeInfo {
     fn get_type_id() -> usize;
     fn get_type_name() -> &'static str;
 }
 
+/// Evaluates IDs of type parameters of mocked generic method, so that failure
+/// about unregistered type uses colors chosen for given scenario.
+/// This method is used by code generated by mocking macros.
+pub fn type_param_ids(scenario: &Lock<ScenarioInternals>, ids: impl FnOnce() -> Vec<usize>) -> Vec<usize> {
+    struct Restore(ColorChoice);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            COLORS.with(|colors| colors.set(self.0));
+        }
+    }
+
+    let colors = lock(scenario).colors;
+    let _restore = Restore(COLORS.with(|current| current.replace(colors)));
+    ids()
+}
+
 /// Panics with message explaining that types used as type parameters of mocked generic
 /// methods must be registered using `register_types` macro.
 /// This method is used by code generated by mocking macros.
+///
+/// Colors are chosen by scenario passed to `type_param_ids`.
 pub fn fail_type_info_not_found() -> ! {
-    panic!("{}", concat!(
-        "\n\n",
-        colored!(red: "error:"),
-        colored!(bold: " Generic method was called with unknown type parameter."),
-        "\n\n",
-        colored!(green: "note:"),
-        " All types which may be used as type parameters for mocked generic methods must be",
-        "registered using `register_types` macro. Use it like this:\n",
-        "\n",
-        "    use mockers::{register_types, mocked};\n",
-        "\n",
-        "    ",
-        colored!(bold: "register_types!(u32, &str);\n"),
-        "\n",
-        "    #[mocked]\n",
-        "    trait A {\n",
-        "        fn generic_method<T>(t: T);\n",
-        "    }\n",
-        "\n",
-        "    #[test]\n",
-        "    #[should_panic]\n",
-        "    fn test_generic_method() {\n",
-        "        let scenario = Scenario::new();\n",
-        "        let mock = scenario.create_mock::<AMock>();\n",
-        "\n",
-        "        scenario.expect(mock.generic_method(\"foo\").and_return(()));\n",
-        "        mock.generic_method(2u32);\n",
-        "    }\n",
-        "\n"
-    ))
+    let colors = COLORS.with(Cell::get).enabled();
+    panic!(
+        concat!(
+            "\n\n{} {}\n\n{}",
+            " All types which may be used as type parameters for mocked generic methods must be",
+            "registered using `register_types` macro. Use it like this:\n",
+            "\n",
+            "    use mockers::{{register_types, mocked}};\n",
+            "\n",
+            "    {}\n",
+            "\n",
+            "    #[mocked]\n",
+            "    trait A {{\n",
+            "        fn generic_method<T>(t: T);\n",
+            "    }}\n",
+            "\n",
+            "    #[test]\n",
+            "    #[should_panic]\n",
+            "    fn test_generic_method() {{\n",
+            "        let scenario = Scenario::new();\n",
+            "        let mock = scenario.create_mock::<AMock>();\n",
+            "\n",
+            "        scenario.expect(mock.generic_method(\"foo\").and_return(()));\n",
+            "        mock.generic_method(2u32);\n",
+            "    }}\n",
+            "\n"
+        ),
+        Style::Red.paint("error:", colors),
+        Style::Bold.paint("Generic method was called with unknown type parameter.", colors),
+        Style::Green.paint("note:", colors),
+        Style::Bold.paint("register_types!(u32, &str);", colors),
+    )
 }
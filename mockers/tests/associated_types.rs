@@ -14,6 +14,20 @@ pub trait B {
     fn create(&self, item: Self::Item) -> Vec<(bool, Self::Item)>;
 }
 
+#[mocked]
+pub trait Store {
+    type Item: Clone + std::fmt::Debug;
+    fn load(&self) -> Self::Item;
+}
+
+#[mocked]
+pub trait Bytes {
+    type Iter<'a>: Iterator<Item = u8>
+    where
+        Self: 'a;
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+}
+
 /// Tests that mock may be created for trait with associated types.
 #[test]
 fn test_associated_type() {
@@ -32,3 +46,26 @@ fn test_self_type_qualification() {
     scenario.expect(handle.create(1).and_return(vec![(true, 2)]));
     assert_eq!(mock.create(1), vec![(true, 2)]);
 }
+
+fn load_twice<S: Store>(store: &S) -> (S::Item, S::Item) {
+    let item = store.load();
+    (item.clone(), item)
+}
+
+/// Tests that bounds of associated types become bounds of mock type parameters.
+#[test]
+fn test_associated_type_bounds() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store<Item = String>>();
+    scenario.expect(handle.load().and_return("a".to_owned()));
+    assert_eq!(load_twice(&mock), ("a".to_owned(), "a".to_owned()));
+}
+
+/// Tests that generic associated types are implemented with mock type parameter.
+#[test]
+fn test_generic_associated_type() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<BytesMock<std::vec::IntoIter<u8>>>();
+    scenario.expect(handle.iter().and_return(vec![1, 2].into_iter()));
+    assert_eq!(mock.iter().collect::<Vec<_>>(), vec![1, 2]);
+}
@@ -0,0 +1,164 @@
+//! Test that async methods, both `async fn` and ones returning boxed future, may be mocked.
+use mockers_derive::mocked;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use mockers::matchers::ANY;
+use mockers::Scenario;
+
+#[mocked]
+trait Storage {
+    async fn get(&self, key: u32) -> String;
+    async fn put(&self, key: u32, value: String);
+}
+
+#[mocked]
+trait Loader {
+    type Item;
+
+    fn load(&self, id: u32) -> Pin<Box<dyn Future<Output = Result<u32, String>> + Send>>;
+    fn next(&self) -> Pin<Box<dyn Future<Output = Option<Self::Item>> + '_>>;
+}
+
+struct NoopWaker;
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls future until it is completed, returns result and number of polls.
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    let mut polls = 1;
+    loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            return (result, polls);
+        }
+        polls += 1;
+    }
+}
+
+/// Future which is pending on first poll and resolves to given value on second one.
+struct Yield<T>(Option<T>, bool);
+impl<T: Unpin> Future for Yield<T> {
+    type Output = T;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        if self.1 {
+            Poll::Ready(self.0.take().unwrap())
+        } else {
+            self.1 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn test_async_fn() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<StorageMock>();
+
+    scenario.expect(handle.put(3, ANY).and_call(|_, value| assert_eq!(value, "three")));
+    scenario.expect(handle.get(3).and_return("three".to_owned()));
+
+    block_on(mock.put(3, "three".to_owned()));
+    assert_eq!(block_on(mock.get(3)).0, "three");
+}
+
+#[test]
+fn test_async_fn_return_clone() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<StorageMock>();
+
+    scenario.expect(handle.get(ANY).and_return_clone("value".to_owned()).times(2));
+
+    assert_eq!(block_on(mock.get(1)).0, "value");
+    assert_eq!(block_on(mock.get(2)).0, "value");
+}
+
+#[test]
+fn test_async_fn_call_is_verified_when_polled() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<StorageMock>();
+
+    let future = mock.get(3);
+    scenario.expect(handle.get(3).and_return("three".to_owned()));
+    assert_eq!(block_on(future).0, "three");
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `Storage#0.get(4)`")]
+fn test_async_fn_unexpected_call() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<StorageMock>();
+
+    scenario.expect(handle.get(3).and_return("three".to_owned()));
+    block_on(mock.get(4));
+}
+
+#[test]
+fn test_boxed_future() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<LoaderMock<u8>>();
+
+    scenario.expect(handle.load(1).and_return(Ok(10)));
+    scenario.expect(handle.load(2).and_return(Err("not found".to_owned())));
+    scenario.expect(handle.next().and_return(Some(7)));
+
+    // Call is verified immediately, before future is polled.
+    let load1 = mock.load(1);
+    let load2 = mock.load(2);
+    assert_eq!(block_on(load2).0, Err("not found".to_owned()));
+    assert_eq!(block_on(load1).0, Ok(10));
+    assert_eq!(block_on(mock.next()).0, Some(7));
+}
+
+#[test]
+fn test_boxed_future_is_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<LoaderMock<u8>>();
+
+    scenario.expect(handle.load(1).and_return(Ok(10)));
+    let future = mock.load(1);
+    assert_send(&future);
+    assert_eq!(block_on(future).0, Ok(10));
+}
+
+#[test]
+fn test_return_future() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<StorageMock>();
+    let (loader, loader_handle) = scenario.create_mock::<LoaderMock<u8>>();
+
+    scenario.expect(handle.get(1).and_return_future(Yield(Some("one".to_owned()), false)));
+    scenario.expect(loader_handle.load(1).and_return_future(Yield(Some(Ok(1)), false)));
+
+    assert_eq!(block_on(mock.get(1)), ("one".to_owned(), 2));
+    assert_eq!(block_on(loader.load(1)), (Ok(1), 2));
+}
+
+// Futures given to `and_return_future` must be `Send` only with `sync` feature
+// or when method returns boxed `Send` future.
+#[cfg(not(feature = "sync"))]
+#[test]
+fn test_return_non_send_future() {
+    use std::rc::Rc;
+
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<StorageMock>();
+    let (loader, loader_handle) = scenario.create_mock::<LoaderMock<u8>>();
+
+    let value = Rc::new("one".to_owned());
+    scenario.expect(handle.get(1).and_return_future(async move { value.as_ref().clone() }));
+    let item = Rc::new(7);
+    scenario.expect(loader_handle.next().and_return_future(async move { Some(*item) }));
+
+    assert_eq!(block_on(mock.get(1)).0, "one");
+    assert_eq!(block_on(loader.next()).0, Some(7));
+}
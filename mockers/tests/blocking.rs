@@ -0,0 +1,136 @@
+//! Test reactions which delay or block calls.
+use mockers_derive::mocked;
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use mockers::matchers::ANY;
+use mockers::{Gate, Scenario};
+
+#[mocked]
+trait Service {
+    fn request(&self, id: u32) -> u32;
+    async fn fetch(&self, id: u32) -> u32;
+}
+
+struct ThreadWaker(Thread);
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls future until it is completed, parking thread while it is pending.
+/// Returns result and number of polls.
+fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    let mut polls = 1;
+    loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+            return (result, polls);
+        }
+        polls += 1;
+        thread::park();
+    }
+}
+
+fn open_later(gate: &Gate) -> thread::JoinHandle<()> {
+    let gate = gate.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        gate.open();
+    })
+}
+
+#[test]
+fn test_delay() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<ServiceMock>();
+
+    scenario.expect(handle.request(ANY).and_delay(Duration::from_millis(50), 3));
+
+    let start = Instant::now();
+    assert_eq!(mock.request(1), 3);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_block_until() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<ServiceMock>();
+    let gate = Gate::new();
+
+    scenario.expect(handle.request(ANY).and_block_until(&gate, 3));
+
+    let opener = open_later(&gate);
+    assert_eq!(mock.request(1), 3);
+    assert!(gate.is_open());
+    opener.join().unwrap();
+}
+
+#[test]
+fn test_block_until_open_gate() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<ServiceMock>();
+    let gate = Gate::new();
+    gate.open();
+
+    scenario.expect(handle.request(ANY).and_block_until(&gate, 3));
+
+    assert_eq!(mock.request(1), 3);
+}
+
+#[test]
+fn test_async_delay() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<ServiceMock>();
+
+    scenario.expect(handle.fetch(ANY).and_delay(Duration::from_millis(50), 3));
+
+    let start = Instant::now();
+    let (result, polls) = block_on(mock.fetch(1));
+    assert_eq!(result, 3);
+    assert!(polls > 1);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn test_async_block_until() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<ServiceMock>();
+    let gate = Gate::new();
+
+    scenario.expect(handle.fetch(ANY).and_block_until(&gate, 3));
+
+    let opener = open_later(&gate);
+    let (result, polls) = block_on(mock.fetch(1));
+    assert_eq!(result, 3);
+    assert!(polls > 1);
+    opener.join().unwrap();
+}
+
+#[test]
+fn test_async_delays_complete_by_deadline() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<ServiceMock>();
+
+    scenario.expect(handle.fetch(1).and_delay(Duration::from_millis(300), 1));
+    scenario.expect(handle.fetch(2).and_delay(Duration::from_millis(20), 2));
+
+    let start = Instant::now();
+    let mut long = Box::pin(mock.fetch(1));
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    assert!(long.as_mut().poll(&mut Context::from_waker(&waker)).is_pending());
+
+    // Later deadline doesn't delay earlier one scheduled after it.
+    assert_eq!(block_on(mock.fetch(2)).0, 2);
+    assert!(start.elapsed() < Duration::from_millis(300));
+
+    assert_eq!(block_on(long).0, 1);
+    assert!(start.elapsed() >= Duration::from_millis(300));
+}
@@ -0,0 +1,88 @@
+//! Test capturing of arguments with `Captor`.
+use mockers_derive::mocked;
+
+use mockers::matchers::{gt, some, Captor, ANY};
+use mockers::Scenario;
+
+#[mocked]
+pub trait Storage {
+    fn put(&self, key: u32, value: String);
+    fn log(&self, message: &str);
+    fn find(&self, key: Option<u32>);
+}
+
+#[test]
+fn test_captor() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Storage>();
+    let keys = Captor::new();
+    let values = Captor::new();
+
+    scenario.expect(handle.put(keys.clone(), values.clone()).and_return_default().times(2));
+    assert_eq!(keys.last(), None);
+
+    mock.put(1, "one".to_owned());
+    mock.put(2, "two".to_owned());
+
+    assert_eq!(keys.values(), vec![1, 2]);
+    assert_eq!(values.values(), vec!["one".to_owned(), "two".to_owned()]);
+    assert_eq!(keys.last(), Some(2));
+}
+
+#[test]
+fn test_captor_matching() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Storage>();
+    let keys = Captor::new();
+
+    scenario.expect(handle.put(ANY, ANY).and_return_default().times(2));
+    scenario.expect(handle.put(keys.matching(gt(2)), ANY).and_return_default().times(1));
+
+    mock.put(1, "one".to_owned());
+    mock.put(3, "three".to_owned());
+    mock.put(2, "two".to_owned());
+
+    assert_eq!(keys.values(), vec![3]);
+}
+
+#[test]
+fn test_captor_for_reference() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Storage>();
+    let messages = Captor::<str>::new();
+
+    scenario.expect(handle.log(messages.clone()).and_return_default().times(1));
+    scenario.expect(handle.log(messages.matching("second")).and_return_default().times(1));
+
+    mock.log("first");
+    mock.log("second");
+
+    assert_eq!(messages.values(), vec!["first".to_owned(), "second".to_owned()]);
+}
+
+#[test]
+fn test_captor_ignores_unmatched_calls() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+    let values = Captor::new();
+
+    scenario.expect(handle.put(1, values.clone()).and_return(()));
+
+    mock.put(2, "two".to_owned());
+    mock.put(1, "one".to_owned());
+
+    assert_eq!(values.values(), vec!["one".to_owned()]);
+}
+
+#[test]
+fn test_captor_inside_matcher() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Storage>();
+    let keys = Captor::new();
+
+    scenario.expect(handle.find(some(keys.clone())).and_return(()));
+
+    mock.find(Some(4));
+
+    assert_eq!(keys.last(), Some(4));
+}
@@ -0,0 +1,146 @@
+//! Test collecting failures and verifying scenario explicitly.
+use mockers_derive::mocked;
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use mockers::matchers::ANY;
+use mockers::report::{CallCountViolation, SourceLocation, UnsatisfiedExpectation, WrongCallCount};
+use mockers::{Failure, Scenario};
+
+pub struct NoDefault;
+
+/// Location of the next line in this file.
+macro_rules! next_line {
+    () => {
+        Some(SourceLocation { file: file!().to_owned(), line: line!() + 1 })
+    };
+}
+
+#[mocked]
+pub trait Store {
+    fn get(&self, key: u32) -> u32;
+    fn create(&self) -> NoDefault;
+}
+
+#[test]
+fn test_verify_ok() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    scenario.expect(handle.get(1).and_return(2));
+    mock.get(1);
+
+    assert_eq!(scenario.verify(), Ok(()));
+}
+
+#[test]
+fn test_verify_unsatisfied() {
+    let scenario = Scenario::new();
+    let (_mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let location = next_line!();
+    scenario.expect(handle.get(1).and_return(2));
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(
+        report.failures(),
+        &[Failure::UnsatisfiedExpectation(UnsatisfiedExpectation {
+            mock_name: "Store#0".to_owned(),
+            expectation: "get(1)".to_owned(),
+            location: location.clone(),
+        })]
+    );
+    assert_eq!(
+        report.to_string(),
+        format!("Some expectations are not satisfied:\n`Store#0.get(1)`\n  declared at {}\n", location.unwrap())
+    );
+}
+
+#[test]
+fn test_collect_unexpected_call() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    scenario.expect(handle.get(1).and_return(2));
+
+    assert_eq!(mock.get(3), 0);
+    assert_eq!(mock.get(1), 2);
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(report.failures().len(), 1);
+    match report.failures()[0] {
+        Failure::UnexpectedCall(ref call) => {
+            assert_eq!(call.method_name, "get");
+            assert_eq!(call.args, "3");
+        }
+        ref other => panic!("unexpected failure: {:?}", other),
+    }
+}
+
+#[test]
+fn test_collect_wrong_call_count() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let location = next_line!();
+    scenario.expect(handle.get(ANY).and_return_clone(2).times(1));
+
+    assert_eq!(mock.get(1), 2);
+    let call_location = next_line!();
+    assert_eq!(mock.get(1), 0);
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(
+        report.failures(),
+        &[Failure::WrongCallCount(WrongCallCount {
+            mock_name: "Store#0".to_owned(),
+            method_name: "get".to_owned(),
+            count: 2,
+            violation: CallCountViolation::TooMany { expected: "called exactly one time".to_owned() },
+            location,
+            call_location,
+        })]
+    );
+}
+
+#[test]
+fn test_collect_failure_of_caught_panic() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let location = next_line!();
+    scenario.expect(handle.create().never());
+
+    let call_location = next_line!();
+    let result = catch_unwind(AssertUnwindSafe(|| mock.create()));
+    assert!(result.is_err());
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(
+        report.failures(),
+        &[Failure::WrongCallCount(WrongCallCount {
+            mock_name: "Store#0".to_owned(),
+            method_name: "create".to_owned(),
+            count: 1,
+            violation: CallCountViolation::NeverExpected,
+            location,
+            call_location,
+        })]
+    );
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `Store#0.get(3)`")]
+fn test_collected_failures_are_reported_on_drop() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    scenario.expect(handle.get(1).and_return(2));
+
+    mock.get(3);
+    mock.get(1);
+}
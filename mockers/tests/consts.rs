@@ -0,0 +1,48 @@
+//! Test mocking traits with associated constants.
+use mockers_derive::mocked;
+
+use mockers::Scenario;
+
+#[mocked(consts(NAME = "fake", MAX_LEN = 16))]
+pub trait Codec {
+    const NAME: &'static str;
+    const MAX_LEN: usize;
+    const VERSION: u32 = 2;
+
+    fn encode(&self, value: &str) -> Vec<u8>;
+}
+
+#[mocked(consts(VERSION = 3))]
+pub trait Versioned {
+    const VERSION: u32 = 1;
+}
+
+fn describe<C: Codec>(_codec: &C) -> String {
+    format!("{} v{}, up to {}", C::NAME, C::VERSION, C::MAX_LEN)
+}
+
+#[test]
+fn test_consts() {
+    assert_eq!(CodecMock::NAME, "fake");
+    assert_eq!(CodecMock::MAX_LEN, 16);
+}
+
+#[test]
+fn test_default_const() {
+    assert_eq!(CodecMock::VERSION, 2);
+}
+
+#[test]
+fn test_overridden_default_const() {
+    assert_eq!(VersionedMock::VERSION, 3);
+}
+
+#[test]
+fn test_mock_with_consts() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<CodecMock>();
+    scenario.expect(handle.encode("a").and_return(vec![97]));
+
+    assert_eq!(describe(&mock), "fake v2, up to 16");
+    assert_eq!(mock.encode("a"), vec![97]);
+}
@@ -0,0 +1,123 @@
+//! Test default reactions used for calls which don't match any expectation.
+use mockers_derive::mocked;
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use mockers::matchers::{ANY, lt};
+use mockers::Scenario;
+
+#[mocked]
+pub trait Config {
+    fn get(&self, key: u32) -> String;
+    fn log(&self, message: &str);
+    fn level(&self) -> u8;
+}
+
+#[mocked]
+trait Fetcher {
+    async fn fetch(&self, id: u32) -> Vec<u8>;
+}
+
+#[test]
+fn test_default_is_used_without_expectations() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    scenario.default_for(handle.get(ANY)).and_return_clone("value".to_owned());
+    scenario.default_for(handle.level()).and_return_default();
+
+    assert_eq!(mock.get(1), "value");
+    assert_eq!(mock.get(2), "value");
+    assert_eq!(mock.level(), 0);
+}
+
+#[test]
+fn test_unused_default_is_not_failure() {
+    let scenario = Scenario::new();
+    let (_mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    scenario.default_for(handle.get(ANY)).and_return_clone("value".to_owned());
+}
+
+#[test]
+fn test_expectation_takes_precedence() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    scenario.default_for(handle.get(ANY)).and_return_clone("default".to_owned());
+    scenario.expect(handle.get(2).and_return("expected".to_owned()));
+
+    assert_eq!(mock.get(1), "default");
+    assert_eq!(mock.get(2), "expected");
+    assert_eq!(mock.get(2), "default");
+}
+
+#[test]
+fn test_later_default_overrides_earlier() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    scenario.default_for(handle.get(ANY)).and_return_clone("any".to_owned());
+    scenario.default_for(handle.get(lt(10))).and_return_clone("small".to_owned());
+
+    assert_eq!(mock.get(1), "small");
+    assert_eq!(mock.get(20), "any");
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `Config#0.get(20)`")]
+fn test_call_not_matching_default() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    scenario.default_for(handle.get(lt(10))).and_return_clone("small".to_owned());
+
+    mock.get(20);
+}
+
+#[test]
+fn test_default_call_clone() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    let logged = Arc::new(AtomicUsize::new(0));
+    let logged_clone = logged.clone();
+    scenario.default_for(handle.log(ANY)).and_call_clone(move |_| {
+        logged_clone.fetch_add(1, Ordering::SeqCst);
+    });
+
+    mock.log("one");
+    mock.log("two");
+    assert_eq!(logged.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_default_survives_checkpoint() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Config>();
+
+    scenario.default_for(handle.level()).and_return_clone(3);
+    scenario.checkpoint();
+
+    assert_eq!(mock.level(), 3);
+}
+
+#[test]
+fn test_default_for_async_method() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<FetcherMock>();
+
+    scenario.default_for(handle.fetch(ANY)).and_return_clone(vec![1, 2]);
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut future = Box::pin(mock.fetch(1));
+    let result = future.as_mut().poll(&mut Context::from_waker(&waker));
+    assert_eq!(result, Poll::Ready(vec![1, 2]));
+}
@@ -0,0 +1,91 @@
+use mockers::matchers::eq;
+use mockers::{ColorChoice, Scenario};
+use mockers_derive::mocked;
+
+#[derive(Debug, PartialEq)]
+pub struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[mocked]
+pub trait Canvas {
+    fn draw(&self, point: Point);
+    fn poly(&self, points: Vec<Point>);
+    fn width(&self, width: u32);
+}
+
+#[test]
+#[should_panic(expected = "    arg #0: Point { x: 2, y: 3 } is not equal to Point { x: 1, y: 3 }
+      diff < expected / > actual:
+        Point {
+      <     x: 1,
+      >     x: 2,
+            y: 3,
+        }
+")]
+fn test_struct_diff() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    let (mock, handle) = scenario.create_mock_for::<dyn Canvas>();
+    scenario.expect(handle.draw(Point { x: 1, y: 3 }).and_return(()));
+    mock.draw(Point { x: 2, y: 3 });
+}
+
+#[test]
+#[should_panic(expected = "
+      diff < expected / > actual:
+        Point {
+      <     x: 1,
+      <     y: 3,
+      >     x: 2,
+      >     y: 4,
+        }
+")]
+fn test_changed_lines_are_grouped() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    let (mock, handle) = scenario.create_mock_for::<dyn Canvas>();
+    scenario.expect(handle.draw(Point { x: 1, y: 3 }).and_return(()));
+    mock.draw(Point { x: 2, y: 4 });
+}
+
+#[test]
+#[should_panic(expected = "
+      diff < expected / > actual:
+        [
+            Point {
+                x: 0,
+                y: 0,
+            },
+            Point {
+      >         x: 5,
+      >         y: 5,
+      >     },
+      >     Point {
+                x: 1,
+                y: 1,
+            },
+        ]
+")]
+fn test_vec_diff_with_eq_matcher() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    let (mock, handle) = scenario.create_mock_for::<dyn Canvas>();
+    scenario.expect(handle.poly(eq(vec![Point { x: 0, y: 0 }, Point { x: 1, y: 1 }])).and_return(()));
+    mock.poly(vec![Point { x: 0, y: 0 }, Point { x: 5, y: 5 }, Point { x: 1, y: 1 }]);
+}
+
+#[test]
+fn test_no_diff_for_scalars() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Canvas>();
+    scenario.expect(handle.width(2).and_return(()));
+    mock.width(3);
+    let report = scenario.verify().unwrap_err();
+    let text = report.to_string();
+    assert!(text.contains("arg #0: 3 is not equal to 2\n"));
+    assert!(!text.contains("diff <"));
+}
@@ -4,7 +4,7 @@
 use mockers_derive::{mocked, register_types};
 
 use mockers::matchers::{any, ANY};
-use mockers::Scenario;
+use mockers::{ColorChoice, Scenario};
 
 register_types!(u32, &str, &u32);
 
@@ -75,6 +75,17 @@ fn test_usage_of_unregistered_parameter_type() {
     scenario.expect(handle.foo(1u8).and_return(()));
 }
 
+/// Test that error about unregistered type follows colors chosen for scenario
+#[test]
+#[should_panic(expected = "\n\nerror: Generic method was called with unknown type parameter.\n\n")]
+fn test_usage_of_unregistered_parameter_type_without_colors() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    let (_mock, handle) = scenario.create_mock::<AMock>();
+
+    scenario.expect(handle.foo(1u8).and_return(()));
+}
+
 /// Test that when call of generic method with some type parameters is expected
 /// call with other type parameters don't match.
 #[test]
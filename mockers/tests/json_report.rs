@@ -0,0 +1,61 @@
+//! Test serialization of verification report to JSON.
+use mockers_derive::mocked;
+
+use mockers::Scenario;
+
+#[mocked]
+pub trait Store {
+    fn get(&self, key: u32) -> u32;
+}
+
+#[test]
+fn test_report_to_json() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    scenario.show_recent_calls(2);
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let at = |line: u32| format!(r#"{{"file":"{}","line":{}}}"#, file!(), line);
+    let line = line!();
+    scenario.expect(handle.get(1).and_return(2));
+    scenario.expect(handle.get(2).never());
+
+    mock.get(2);
+    mock.get(3);
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(
+        report.failures()[0].to_json(),
+        format!(
+            concat!(
+                r#"{{"kind":"wrong_call_count","mock_name":"Store#0","method_name":"get","count":1,"#,
+                r#""violation":{{"kind":"never_expected"}},"location":{},"call_location":{}}}"#
+            ),
+            at(line + 2),
+            at(line + 4)
+        )
+    );
+    assert_eq!(
+        report.to_json(),
+        format!(
+            concat!(
+                r#"{{"failures":["#,
+                r#"{{"kind":"wrong_call_count","mock_name":"Store#0","method_name":"get","count":1,"#,
+                r#""violation":{{"kind":"never_expected"}},"location":{get_2},"call_location":{call_2}}},"#,
+                r#"{{"kind":"unexpected_call","mock_name":"Store#0","method_name":"get","args":"3","#,
+                r#""call_location":{call_3},"recent_calls":["#,
+                r#"{{"mock_name":"Store#0","method_name":"get","args":"2","location":{call_2}}}],"#,
+                r#""not_strict":false,"no_expectations":false,"out_of_order":[],"candidates":["#,
+                r#"{{"expectation":"get(1)","location":{get_1},"#,
+                r#""args":[{{"index":0,"matcher":"1","error":"3 is not equal to 1"}}]}}"#,
+                r#"],"other_mocks":[]}},"#,
+                r#"{{"kind":"unsatisfied_expectation","mock_name":"Store#0","expectation":"get(1)","location":{get_1}}}"#,
+                r#"]}}"#
+            ),
+            get_1 = at(line + 1),
+            get_2 = at(line + 2),
+            call_2 = at(line + 4),
+            call_3 = at(line + 5)
+        )
+    );
+}
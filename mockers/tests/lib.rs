@@ -2,7 +2,7 @@ use std::panic::AssertUnwindSafe;
 use std::rc::Rc;
 
 use mockers::matchers::{lt, ANY};
-use mockers::{Scenario, Sequence};
+use mockers::{ColorChoice, Scenario, Sequence};
 use mockers_derive::{mock, mocked};
 
 #[mocked]
@@ -274,6 +274,20 @@ fn test_format_args() {
 #[should_panic(expected = "expectation `\x1b[1mA#0\x1b[0m.bar(12)`")]
 fn test_check_other_mock_object_expectations() {
     let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Always);
+    let (_mock0, handle0) = scenario.create_mock_for::<dyn A>();
+    let (mock1, _) = scenario.create_mock_for::<dyn A>();
+
+    scenario.expect(handle0.bar(12).and_return(()));
+
+    mock1.bar(12);
+}
+
+#[test]
+#[should_panic(expected = "\n\nerror: unexpected call to `A#1.bar(12)`\n\n")]
+fn test_failure_without_colors() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
     let (_mock0, handle0) = scenario.create_mock_for::<dyn A>();
     let (mock1, _) = scenario.create_mock_for::<dyn A>();
 
@@ -0,0 +1,96 @@
+//! Test mocking traits and methods with lifetime parameters.
+use mockers_derive::mocked;
+
+use mockers::Scenario;
+
+#[derive(Debug, PartialEq)]
+pub struct Token<'a>(&'a str);
+
+#[mocked]
+pub trait Tokenizer: 'static {
+    fn first<'a>(&self, input: &'a str) -> Token<'a>;
+    fn len<'a>(&self, token: Token<'a>) -> usize;
+}
+
+#[mocked]
+pub trait Visitor<'de> {
+    fn visit_str(&mut self, value: &'de str);
+    fn borrowed(&self) -> &'de str;
+}
+
+#[mocked]
+pub trait Source<'a, T> {
+    fn next(&mut self) -> Option<&'a T>;
+}
+
+#[mocked]
+pub trait Holder<'a, T: 'a> {
+    fn get(&self) -> &'a T;
+}
+
+fn visit_words<'de, V: Visitor<'de>>(visitor: &mut V, input: &'de str) {
+    for word in input.split(' ') {
+        visitor.visit_str(word);
+    }
+}
+
+#[test]
+fn test_method_lifetime_params() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Tokenizer>();
+
+    scenario.expect(handle.first("foo bar").and_return(Token("foo")));
+    scenario.expect(handle.len(Token("foo")).and_return(3));
+
+    let input = String::from("foo bar");
+    let token = mock.first(&input);
+    assert_eq!(token, Token("foo"));
+    let owned = String::from("foo");
+    assert_eq!(mock.len(Token(&owned)), 3);
+}
+
+#[test]
+fn test_trait_lifetime_param() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock_for::<dyn Visitor>();
+
+    scenario.expect(handle.visit_str("foo").and_return(()));
+    scenario.expect(handle.borrowed().and_return("bar"));
+
+    mock.visit_str("foo");
+    assert_eq!(mock.borrowed(), "bar");
+}
+
+#[test]
+fn test_trait_lifetime_param_with_borrowed_data() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock::<VisitorMock>();
+
+    scenario.expect(handle.visit_str("foo").and_return(()));
+    scenario.expect(handle.visit_str("bar").and_return(()));
+
+    let input = String::from("foo bar");
+    visit_words(&mut mock, &input);
+}
+
+#[test]
+fn test_trait_lifetime_and_type_params() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock_for::<dyn Source<u32>>();
+
+    static VALUE: u32 = 5;
+    scenario.expect(handle.next().and_return(Some(&VALUE)));
+
+    assert_eq!(mock.next(), Some(&5));
+}
+
+#[test]
+fn test_type_param_bounded_by_trait_lifetime() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Holder<u32>>();
+
+    static VALUE: u32 = 7;
+    scenario.expect(handle.get().and_return(&VALUE));
+
+    assert_eq!(*mock.get(), 7);
+}
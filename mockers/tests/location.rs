@@ -0,0 +1,216 @@
+//! Test reporting of places where expectations are declared.
+use mockers_derive::mocked;
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Wake, Waker};
+
+use mockers::matchers::ANY;
+use mockers::{ColorChoice, Failure, Scenario, Sequence};
+
+#[mocked]
+pub trait Store {
+    fn get(&self, key: u32) -> u32;
+    fn put(&self, key: u32, value: u32);
+}
+
+#[mocked]
+trait AsyncStore {
+    async fn get(&self, key: u32) -> u32;
+}
+
+struct NoopWaker;
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+#[test]
+fn test_unsatisfied_expectation_location() {
+    let scenario = Scenario::new();
+    let (_mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let line = line!();
+    scenario.expect(handle.get(1).and_return(2));
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(
+        report.to_string(),
+        format!("Some expectations are not satisfied:\n`Store#0.get(1)`\n  declared at {}:{}\n", file!(), line + 1)
+    );
+}
+
+#[test]
+fn test_location_of_handle_call_in_multiline_expectation() {
+    let scenario = Scenario::new();
+    let (_mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let line = line!();
+    scenario.expect(
+        handle.put(1, 2)
+            .and_return(()),
+    );
+
+    let report = scenario.verify().unwrap_err();
+    match report.failures()[0] {
+        Failure::UnsatisfiedExpectation(ref failure) => {
+            let location = failure.location.as_ref().unwrap();
+            assert_eq!(location.file, file!());
+            assert_eq!(location.line, line + 2);
+        }
+        ref other => panic!("unexpected failure: {:?}", other),
+    }
+}
+
+#[test]
+fn test_candidate_location() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let line = line!();
+    scenario.expect(handle.get(1).and_return(2));
+    scenario.expect(handle.get(2).and_return(3));
+
+    mock.get(3);
+
+    let report = scenario.verify().unwrap_err();
+    let text = report.to_string();
+    assert!(text.contains(&format!(
+        concat!(
+            "  expectation `Store#0.get(2)`\n",
+            "    declared at {file}:{second}\n",
+            "    arg #0: 3 is not equal to 2\n\n\n",
+            "  expectation `Store#0.get(1)`\n",
+            "    declared at {file}:{first}\n",
+            "    arg #0: 3 is not equal to 1\n",
+        ),
+        file = file!(),
+        first = line + 1,
+        second = line + 2
+    )));
+}
+
+#[test]
+fn test_saturated_expectation_location() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let line = line!();
+    scenario.expect(handle.get(1).and_return(2));
+
+    mock.get(1);
+    mock.get(1);
+
+    let report = scenario.verify().unwrap_err();
+    assert_eq!(
+        report.failures()[0].to_string(),
+        format!(
+            "Store#0.get was already called earlier\n  expectation is declared at {file}:{expect}\n  called at {file}:{call}",
+            file = file!(),
+            expect = line + 1,
+            call = line + 4
+        )
+    );
+}
+
+#[test]
+fn test_out_of_order_locations() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    scenario.collect_failures();
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    let seq = Sequence::new();
+    let line = line!();
+    scenario.expect(handle.put(1, 2).and_return(()).in_sequence(&seq));
+    scenario.expect(handle.get(1).and_return(2).in_sequence(&seq));
+
+    mock.get(1);
+    mock.put(1, 2);
+
+    let report = scenario.verify().unwrap_err();
+    assert!(report.to_string().contains(&format!(
+        concat!(
+            "  `Store#0.put(1, 2)` is not satisfied yet\n",
+            "  expectation is declared at {file}:{get}\n",
+            "  prerequisite is declared at {file}:{put}\n",
+        ),
+        file = file!(),
+        put = line + 1,
+        get = line + 2
+    )));
+}
+
+#[test]
+fn test_unexpected_call_location() {
+    let scenario = Scenario::new();
+    scenario.collect_failures();
+    let (mock, _handle) = scenario.create_mock_for::<dyn Store>();
+
+    let line = line!();
+    mock.get(1);
+
+    let report = scenario.verify().unwrap_err();
+    match report.failures()[0] {
+        Failure::UnexpectedCall(ref failure) => {
+            let location = failure.call_location.as_ref().unwrap();
+            assert_eq!(location.file, file!());
+            assert_eq!(location.line, line + 1);
+        }
+        ref other => panic!("unexpected failure: {:?}", other),
+    }
+}
+
+#[test]
+fn test_unexpected_async_call_location() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    let (mock, _handle) = scenario.create_mock::<AsyncStoreMock>();
+
+    let line = line!();
+    let future = mock.get(1);
+
+    // Call is verified when future is polled, but location is where method is called.
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let panic = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _ = pin!(future).poll(&mut Context::from_waker(&waker));
+    }))
+    .unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains(&format!("called at {}:{}", file!(), line + 1)));
+}
+
+#[test]
+fn test_recent_calls() {
+    let scenario = Scenario::new();
+    scenario.set_colors(ColorChoice::Never);
+    scenario.collect_failures();
+    scenario.show_recent_calls(2);
+    let (mock, handle) = scenario.create_mock_for::<dyn Store>();
+
+    scenario.expect(handle.put(ANY, ANY).and_return_default().times(3));
+
+    let line = line!();
+    mock.put(1, 0);
+    mock.put(2, 0);
+    mock.put(3, 0);
+    mock.get(1);
+
+    let report = scenario.verify().unwrap_err();
+    assert!(report.to_string().contains(&format!(
+        concat!(
+            "note: called at {file}:{get}\n\n",
+            "note: calls received before this one:\n",
+            "  `Store#0.put(2, 0)` at {file}:{put_2}\n",
+            "  `Store#0.put(3, 0)` at {file}:{put_3}\n\n",
+        ),
+        file = file!(),
+        put_2 = line + 2,
+        put_3 = line + 3,
+        get = line + 4
+    )));
+}
@@ -0,0 +1,66 @@
+//! Test that methods with many arguments can be mocked.
+use mockers::matchers::{lt, ANY};
+use mockers::Scenario;
+use mockers_derive::mocked;
+
+#[mocked]
+pub trait A {
+    fn five(&self, a0: u8, a1: u8, a2: u8, a3: u8, a4: u8) -> u32;
+    #[allow(clippy::too_many_arguments)]
+    fn twelve(&self, a0: u8, a1: u8, a2: u8, a3: u8, a4: u8, a5: u8,
+              a6: u8, a7: u8, a8: u8, a9: u8, a10: u8, a11: &str) -> u32;
+    #[allow(clippy::too_many_arguments)]
+    fn twelve_static(a0: u8, a1: u8, a2: u8, a3: u8, a4: u8, a5: u8,
+                     a6: u8, a7: u8, a8: u8, a9: u8, a10: u8, a11: u8);
+}
+
+#[test]
+fn test_five_args() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<AMock>();
+
+    scenario.expect(handle.five(1, 2, 3, 4, 5).and_call(|a0, _, _, _, a4| u32::from(a0 + a4)));
+
+    assert_eq!(mock.five(1, 2, 3, 4, 5), 6);
+}
+
+#[test]
+fn test_twelve_args() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<AMock>();
+
+    scenario.expect(
+        handle.twelve(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, lt(11), "eleven")
+              .and_call_clone(|a0, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11: &str| {
+                  [a0, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10].iter().map(|&a| u32::from(a)).sum::<u32>()
+                      + a11.len() as u32
+              })
+              .times(2),
+    );
+
+    assert_eq!(mock.twelve(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, "eleven"), 61);
+    assert_eq!(mock.twelve(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, "eleven"), 61);
+}
+
+#[test]
+fn test_twelve_args_static() {
+    let scenario = Scenario::new();
+    let (_mock, handle) = scenario.create_mock::<AMockStatic>();
+
+    scenario.expect(handle.twelve_static(ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, 11)
+                          .and_return(()));
+
+    AMock::twelve_static(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11);
+}
+
+#[test]
+#[should_panic(expected = "\"twelve\" is not equal to \"eleven\"")]
+fn test_twelve_args_mismatch() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<AMock>();
+
+    scenario.expect(handle.twelve(ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, ANY, "eleven")
+                          .and_return(0));
+
+    mock.twelve(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, "twelve");
+}
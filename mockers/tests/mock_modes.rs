@@ -0,0 +1,123 @@
+//! Test nice and naggy mocks.
+use mockers_derive::mocked;
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use mockers::{MockMode, Scenario};
+
+#[derive(Debug, PartialEq)]
+pub struct Token(u32);
+
+#[mocked]
+pub trait A {
+    fn foo(&self, arg: u32) -> u32;
+    fn bar(&self) -> String;
+    fn baz(&self);
+    fn token(&self) -> Token;
+}
+
+#[mocked]
+trait Fetcher {
+    async fn fetch(&self, id: u32) -> Vec<u8>;
+}
+
+#[test]
+fn test_nice_mock_returns_default() {
+    let scenario = Scenario::new();
+    let (mock, _handle) = scenario.create_nice_mock_for::<dyn A>();
+
+    assert_eq!(mock.foo(3), 0);
+    assert_eq!(mock.bar(), "");
+    mock.baz();
+}
+
+#[test]
+fn test_nice_mock_uses_expectations() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn A>();
+
+    scenario.expect(handle.foo(3).and_return(7));
+
+    assert_eq!(mock.foo(2), 0);
+    assert_eq!(mock.foo(3), 7);
+}
+
+#[test]
+#[should_panic(expected = "Some expectations are not satisfied")]
+fn test_nice_mock_verifies_expectations() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn A>();
+
+    scenario.expect(handle.foo(3).and_return(7));
+
+    mock.foo(2);
+}
+
+#[test]
+#[should_panic(expected = "mock isn't strict, but method result type doesn't implement `Default`")]
+fn test_nice_mock_without_default_result() {
+    let scenario = Scenario::new();
+    let (mock, _handle) = scenario.create_nice_mock_for::<dyn A>();
+
+    mock.token();
+}
+
+#[test]
+fn test_naggy_mock_returns_default() {
+    let scenario = Scenario::new();
+    let (mock, _handle) = scenario.create_naggy_mock::<AMock>();
+
+    assert_eq!(mock.foo(3), 0);
+    assert_eq!(mock.bar(), "");
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `A#1.foo(3)`")]
+fn test_mode_is_per_mock() {
+    let scenario = Scenario::new();
+    let (nice, _nice_handle) = scenario.create_nice_mock_for::<dyn A>();
+    let (strict, _strict_handle) = scenario.create_mock_for::<dyn A>();
+
+    nice.foo(3);
+    strict.foo(3);
+}
+
+#[test]
+fn test_named_nice_mock() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_named_mock_for_with_mode::<dyn A>("amock".to_owned(), MockMode::Nice);
+    let (typed, _typed_handle) = scenario.create_named_mock_with_mode::<AMock>("typed".to_owned(), MockMode::Naggy);
+
+    scenario.expect(handle.foo(3).and_return(7));
+
+    assert_eq!(mock.foo(2), 0);
+    assert_eq!(mock.foo(3), 7);
+    assert_eq!(typed.bar(), "");
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `amock.foo(3)`")]
+fn test_named_strict_mock() {
+    let scenario = Scenario::new();
+    let (mock, _handle) = scenario.create_named_mock_for_with_mode::<dyn A>("amock".to_owned(), MockMode::Strict);
+
+    mock.foo(3);
+}
+
+#[test]
+fn test_nice_async_mock() {
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let scenario = Scenario::new();
+    let (mock, _handle) = scenario.create_nice_mock::<FetcherMock>();
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut future = Box::pin(mock.fetch(1));
+    let result = future.as_mut().poll(&mut Context::from_waker(&waker));
+    assert_eq!(result, Poll::Ready(vec![]));
+}
@@ -0,0 +1,92 @@
+//! Test reactions modifying arguments passed by mutable reference.
+use mockers_derive::mocked;
+
+use mockers::args::{ARG0, ARG1};
+use mockers::matchers::ANY;
+use mockers::Scenario;
+
+#[mocked]
+pub trait Reader {
+    fn read(&mut self, buf: &mut [u8]) -> usize;
+    fn read_len(&self, len: &mut usize, buf: &mut Vec<u8>) -> bool;
+}
+
+#[test]
+fn test_copy_into_slice() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock_for::<dyn Reader>();
+
+    scenario.expect(handle.read(ANY).and_copy_into_slice(ARG0, b"abc").and_return(3));
+
+    let mut buf = [0u8; 5];
+    assert_eq!(mock.read(&mut buf), 3);
+    assert_eq!(&buf, b"abc\0\0");
+}
+
+#[test]
+#[should_panic(expected = "slice argument is too short to copy data into: 2 < 3")]
+fn test_copy_into_short_slice() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock_for::<dyn Reader>();
+
+    scenario.expect(handle.read(ANY).and_copy_into_slice(ARG0, b"abc").and_return(3));
+
+    let mut buf = [0u8; 2];
+    mock.read(&mut buf);
+}
+
+#[test]
+fn test_set_arg() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Reader>();
+
+    scenario.expect(
+        handle
+            .read_len(ANY, ANY)
+            .and_set_arg(ARG0, 2)
+            .and_set_arg(ARG1, vec![1, 2])
+            .and_return(true),
+    );
+
+    let mut len = 0;
+    let mut buf = Vec::new();
+    assert!(mock.read_len(&mut len, &mut buf));
+    assert_eq!(len, 2);
+    assert_eq!(buf, vec![1, 2]);
+}
+
+#[test]
+fn test_assign_with_repeated_reaction() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Reader>();
+
+    scenario.expect(
+        handle
+            .read_len(ANY, ANY)
+            .and_assign_with(ARG1, |buf: &mut Vec<u8>| buf.push(7))
+            .and_return_clone(true)
+            .times(2),
+    );
+
+    let mut len = 0;
+    let mut buf = Vec::new();
+    mock.read_len(&mut len, &mut buf);
+    mock.read_len(&mut len, &mut buf);
+    assert_eq!(buf, vec![7, 7]);
+}
+
+#[test]
+fn test_args_are_modified_before_action() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock_for::<dyn Reader>();
+
+    scenario.expect(
+        handle
+            .read(ANY)
+            .and_copy_into_slice(ARG0, &[1, 2])
+            .and_call(|buf: &mut [u8]| buf.iter().map(|&b| b as usize).sum()),
+    );
+
+    let mut buf = [0u8; 3];
+    assert_eq!(mock.read(&mut buf), 3);
+}
@@ -0,0 +1,211 @@
+//! Test partial mocks forwarding calls to real implementation.
+use mockers_derive::mocked;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use mockers::matchers::ANY;
+use mockers::Scenario;
+
+#[mocked(record_args)]
+pub trait Storage {
+    fn get(&self, key: u32) -> String;
+    fn put(&mut self, key: u32, value: String);
+    fn size(&self) -> usize;
+}
+
+struct RealStorage {
+    values: Vec<(u32, String)>,
+}
+
+impl Storage for RealStorage {
+    fn get(&self, key: u32) -> String {
+        self.values
+            .iter()
+            .find(|&&(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_default()
+    }
+    fn put(&mut self, key: u32, value: String) {
+        self.values.push((key, value));
+    }
+    fn size(&self) -> usize {
+        self.values.len()
+    }
+}
+
+fn real_storage() -> Box<RealStorage> {
+    Box::new(RealStorage { values: vec![(1, "one".to_owned())] })
+}
+
+#[test]
+fn test_calls_are_forwarded() {
+    let scenario = Scenario::new();
+    let (mut mock, _handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    mock.put(2, "two".to_owned());
+
+    assert_eq!(mock.get(1), "one");
+    assert_eq!(mock.get(2), "two");
+    assert_eq!(mock.size(), 2);
+}
+
+#[test]
+fn test_expectations_override_real_implementation() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    scenario.expect(handle.get(2).and_return("fake".to_owned()));
+
+    assert_eq!(mock.get(1), "one");
+    assert_eq!(mock.get(2), "fake");
+}
+
+#[test]
+#[should_panic(expected = "Storage#0.get is called for the 2nd time, but expected to be called exactly one time")]
+fn test_exhausted_expectations_are_not_forwarded() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    scenario.expect(handle.get(2).and_return_clone("fake".to_owned()).times(1));
+
+    mock.get(2);
+    mock.get(2);
+}
+
+#[test]
+#[should_panic(expected = "Some expectations are not satisfied")]
+fn test_expectations_are_verified() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    scenario.expect(handle.get(2).and_return("fake".to_owned()));
+
+    mock.get(1);
+}
+
+#[test]
+fn test_forwarded_calls_are_recorded() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    mock.put(2, "two".to_owned());
+    mock.get(2);
+
+    assert!(handle.put(2, ANY).was_called());
+    assert_eq!(handle.get(ANY).call_count(), 1);
+    assert_eq!(handle.size().call_count(), 0);
+}
+
+#[test]
+fn test_expected_calls_are_not_recorded() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    scenario.expect(handle.get(2).and_return("fake".to_owned()));
+
+    mock.get(1);
+    mock.get(2);
+
+    assert_eq!(handle.get(ANY).call_count(), 1);
+    assert!(!handle.get(2).was_called());
+}
+
+#[test]
+fn test_all_calls_are_recorded_once_with_record_calls() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+
+    scenario.expect(handle.get(2).and_return("fake".to_owned()));
+
+    mock.get(1);
+    mock.get(2);
+
+    assert_eq!(handle.get(ANY).call_count(), 2);
+}
+
+#[test]
+#[should_panic(expected = "calls are not recorded, enable recording with `Scenario::record_calls`")]
+fn test_partial_mock_doesnt_enable_recording_for_other_mocks() {
+    let scenario = Scenario::new();
+    let (_partial, _partial_handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+    let (mock, handle) = scenario.create_mock_for::<dyn Storage>();
+
+    scenario.expect(handle.get(1).and_return("fake".to_owned()));
+    mock.get(1);
+
+    handle.get(1).was_called();
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `Storage#1.get(1)`")]
+fn test_mock_without_delegate_is_strict() {
+    let scenario = Scenario::new();
+    let (_partial, _partial_handle) = scenario.create_partial_mock_for::<dyn Storage>(real_storage());
+    let (mock, _handle) = scenario.create_mock_for::<dyn Storage>();
+
+    mock.get(1);
+}
+
+#[mocked]
+pub trait Source<T> {
+    type Item;
+    fn next(&self, hint: T) -> Option<Self::Item>;
+}
+
+struct Counter(AtomicUsize);
+
+impl Source<u32> for Counter {
+    type Item = usize;
+    fn next(&self, hint: u32) -> Option<usize> {
+        Some(self.0.fetch_add(hint as usize, Ordering::SeqCst))
+    }
+}
+
+#[test]
+fn test_generic_trait_with_associated_type() {
+    let scenario = Scenario::new();
+    let (mock, handle) =
+        scenario.create_partial_mock_for::<dyn Source<u32, Item = usize>>(Box::new(Counter(AtomicUsize::new(0))));
+
+    scenario.expect(handle.next(0).and_return(None));
+
+    assert_eq!(mock.next(2), Some(0));
+    assert_eq!(mock.next(0), None);
+    assert_eq!(mock.next(1), Some(2));
+}
+
+#[mocked]
+pub trait Loader {
+    fn load(&self, id: u32) -> Pin<Box<dyn Future<Output = u32> + Send>>;
+}
+
+struct RealLoader;
+
+impl Loader for RealLoader {
+    fn load(&self, id: u32) -> Pin<Box<dyn Future<Output = u32> + Send>> {
+        Box::pin(async move { id * 10 })
+    }
+}
+
+#[test]
+fn test_boxed_future_is_forwarded() {
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_partial_mock_for::<dyn Loader>(Box::new(RealLoader));
+
+    scenario.expect(handle.load(1).and_return(5));
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    assert_eq!(mock.load(1).as_mut().poll(&mut context), Poll::Ready(5));
+    assert_eq!(mock.load(2).as_mut().poll(&mut context), Poll::Ready(20));
+}
@@ -0,0 +1,175 @@
+//! Test recording of calls and inspecting them after they are made.
+use mockers_derive::mocked;
+
+use std::borrow::Cow;
+
+use mockers::matchers::{ANY, any, gt};
+use mockers::record::RecordedCall;
+use mockers::Scenario;
+
+#[derive(Debug, PartialEq)]
+pub struct Token(u32);
+
+#[mocked(record_args(put, log, read, flush))]
+pub trait Storage {
+    fn put(&self, key: u32, value: String);
+    fn put_token(&self, token: Token);
+    fn log(&self, message: &str);
+    fn read(&self, buf: &mut [u8]);
+    fn flush(&self);
+}
+
+/// Arguments aren't recorded by default, so their types don't have to be
+/// cloneable or own their data.
+#[mocked]
+pub trait Printer {
+    fn print<'a>(&self, text: Cow<'a, str>);
+}
+
+#[test]
+fn test_was_called() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.put(1, "one".to_owned());
+
+    assert!(handle.put(1, ANY).was_called());
+    assert!(!handle.put(2, ANY).was_called());
+    assert!(!handle.flush().was_called());
+}
+
+#[test]
+fn test_call_count() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+    let (other, _other_handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.put(1, "one".to_owned());
+    mock.put(2, "two".to_owned());
+    mock.put(3, "three".to_owned());
+    mock.flush();
+    other.flush();
+
+    assert_eq!(handle.put(ANY, ANY).call_count(), 3);
+    assert_eq!(handle.put(gt(1), ANY).call_count(), 2);
+    assert_eq!(handle.flush().call_count(), 1);
+}
+
+#[test]
+fn test_calls() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_named_mock_for::<dyn Storage>("storage".to_owned());
+
+    scenario.expect(handle.put(1, ANY).and_return(()));
+    mock.put(1, "one".to_owned());
+
+    let calls = handle.put(ANY, ANY).calls::<(u32, String)>();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].mock_name, "storage");
+    assert_eq!(calls[0].method_name, "put");
+    assert_eq!(calls[0].args_debug, "1, \"one\"");
+    assert_eq!(calls[0].args, Some((1, "one".to_owned())));
+}
+
+#[test]
+fn test_borrowed_args_are_recorded() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.log("message");
+    mock.read(&mut [1, 2]);
+
+    assert!(handle.log("message").was_called());
+    assert!(!handle.log("other").was_called());
+    assert_eq!(handle.read(ANY).call_count(), 1);
+}
+
+/// Recorded references are returned as owned values, so they may be used
+/// after scenario is dropped.
+fn logged_calls() -> Vec<RecordedCall<(String,)>> {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    let message = String::from("message");
+    mock.log(&message);
+    mock.read(&mut [1, 2]);
+
+    assert_eq!(handle.read(ANY).calls::<(Vec<u8>,)>()[0].args, Some((vec![1, 2],)));
+    handle.log(ANY).calls()
+}
+
+#[test]
+fn test_recorded_calls_outlive_scenario() {
+    let calls = logged_calls();
+    let _other = vec!["x".repeat(32); 16];
+
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].args, Some(("message".to_owned(),)));
+}
+
+#[test]
+#[should_panic(expected = "arguments of `Storage#0.log` are recorded as `(alloc::string::String,)`, not `(&str,)`")]
+fn test_calls_with_wrong_args_type() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.log("message");
+
+    handle.log(ANY).calls::<(&'static str,)>();
+}
+
+#[test]
+fn test_args_borrowing_data_are_not_recorded() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_mock_for::<dyn Printer>();
+
+    scenario.expect(handle.print(ANY).and_return(()));
+    mock.print(Cow::Borrowed("text"));
+
+    assert_eq!(handle.print(ANY).call_count(), 1);
+    let calls = handle.print(ANY).calls::<(Cow<'static, str>,)>();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].args_debug, "\"text\"");
+    assert_eq!(calls[0].args, None);
+}
+
+#[test]
+fn test_unrecorded_args_match_any() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.put_token(Token(1));
+
+    assert!(handle.put_token(ANY).was_called());
+    assert_eq!(handle.put_token(any()).call_count(), 1);
+}
+
+#[test]
+fn test_unrecorded_args_dont_match_other_matchers() {
+    let scenario = Scenario::new();
+    scenario.record_calls();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.put_token(Token(1));
+
+    assert!(!handle.put_token(Token(1)).was_called());
+}
+
+#[test]
+#[should_panic(expected = "calls are not recorded, enable recording with `Scenario::record_calls`")]
+fn test_recording_is_disabled_by_default() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_nice_mock_for::<dyn Storage>();
+
+    mock.flush();
+
+    handle.flush().was_called();
+}
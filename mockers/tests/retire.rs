@@ -0,0 +1,85 @@
+//! Test retirement of expectations which reached expected number of calls.
+use mockers_derive::mocked;
+
+use mockers::matchers::ANY;
+use mockers::{Scenario, Sequence};
+
+#[mocked]
+pub trait Source {
+    fn get(&self, key: u32) -> u32;
+}
+
+#[test]
+fn test_retire_on_saturation() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Source>();
+
+    scenario.expect(handle.get(ANY).and_return_clone(2).times(..));
+    scenario.expect(handle.get(ANY).and_return_clone(1).times(2).retire_on_saturation());
+
+    assert_eq!(mock.get(0), 1);
+    assert_eq!(mock.get(0), 1);
+    assert_eq!(mock.get(0), 2);
+    assert_eq!(mock.get(0), 2);
+}
+
+#[test]
+#[should_panic(expected = "Source#0.get is called for the 3rd time, but expected to be called exactly 2 times")]
+fn test_saturated_expectation_matches_by_default() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Source>();
+
+    scenario.expect(handle.get(ANY).and_return_clone(2).times(..));
+    scenario.expect(handle.get(ANY).and_return_clone(1).times(2));
+
+    mock.get(0);
+    mock.get(0);
+    mock.get(0);
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `Source#0.get(0)`")]
+fn test_retired_expectation_without_fallback() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Source>();
+
+    scenario.expect(handle.get(ANY).and_return_clone(1).times(1).retire_on_saturation());
+
+    mock.get(0);
+    mock.get(0);
+}
+
+#[test]
+fn test_scenario_retire_on_saturation() {
+    let scenario = Scenario::new();
+    scenario.retire_on_saturation();
+    let (mock, handle) = scenario.create_mock_for::<dyn Source>();
+
+    scenario.expect(handle.get(ANY).and_return_clone(3).times(1));
+    scenario.expect(handle.get(ANY).and_return(2));
+    scenario.expect(handle.get(ANY).and_return(1));
+
+    assert_eq!(mock.get(0), 1);
+    assert_eq!(mock.get(0), 2);
+    assert_eq!(mock.get(0), 3);
+}
+
+#[test]
+fn test_retire_in_sequence() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Source>();
+    let seq = Sequence::new();
+
+    scenario.expect(handle.get(1).and_return_clone(10).times(..));
+    scenario.expect(
+        handle
+            .get(ANY)
+            .and_return_clone(0)
+            .times(1)
+            .retire_on_saturation()
+            .in_sequence(&seq),
+    );
+
+    assert_eq!(mock.get(1), 0);
+    assert_eq!(mock.get(1), 10);
+}
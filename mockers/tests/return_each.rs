@@ -0,0 +1,88 @@
+//! Test returning sequence of values with `and_return_each`.
+use mockers_derive::mocked;
+
+use mockers::matchers::ANY;
+use mockers::Scenario;
+
+#[mocked]
+pub trait Counter {
+    fn next(&self) -> u32;
+    fn label(&self, index: u32) -> String;
+}
+
+#[test]
+fn test_return_each() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Counter>();
+
+    scenario.expect(handle.next().and_return_each(vec![1, 2, 3]));
+
+    assert_eq!(mock.next(), 1);
+    assert_eq!(mock.next(), 2);
+    assert_eq!(mock.next(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Counter#0.next is called for the 3rd time, but expected to be called exactly 2 times")]
+fn test_return_each_too_many_calls() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Counter>();
+
+    scenario.expect(handle.next().and_return_each(vec![1, 2]));
+
+    mock.next();
+    mock.next();
+    mock.next();
+}
+
+#[test]
+#[should_panic(expected = "next() must be called exactly 2 times, called 1 times")]
+fn test_return_each_too_few_calls() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Counter>();
+
+    scenario.expect(handle.next().and_return_each(vec![1, 2]));
+
+    mock.next();
+}
+
+#[test]
+fn test_return_each_then_return_clone() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Counter>();
+
+    scenario.expect(
+        handle
+            .label(ANY)
+            .and_return_each(vec!["first".to_owned()])
+            .then_return_clone("rest".to_owned()),
+    );
+
+    assert_eq!(mock.label(0), "first");
+    assert_eq!(mock.label(1), "rest");
+    assert_eq!(mock.label(2), "rest");
+}
+
+#[test]
+#[should_panic(expected = "no more values")]
+fn test_return_each_then_panic() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Counter>();
+
+    scenario.expect(handle.next().and_return_each(vec![1]).then_panic("no more values".to_owned()));
+
+    mock.next();
+    mock.next();
+}
+
+#[test]
+fn test_times_then_return_clone() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Counter>();
+
+    scenario.expect(handle.next().and_return_clone(1).times(2).then_return_clone(0));
+
+    assert_eq!(mock.next(), 1);
+    assert_eq!(mock.next(), 1);
+    assert_eq!(mock.next(), 0);
+}
@@ -0,0 +1,64 @@
+//! Test reactions creating result with factory function on each call.
+use mockers_derive::mocked;
+
+use std::io;
+
+use mockers::matchers::ANY;
+use mockers::Scenario;
+
+#[derive(Debug, PartialEq)]
+pub struct Token(u32);
+
+#[mocked]
+pub trait Connection {
+    fn token(&self) -> Token;
+    fn send(&self, data: &[u8]) -> Result<usize, io::Error>;
+    fn receive(&self) -> Result<Token, String>;
+}
+
+#[test]
+fn test_return_with() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Connection>();
+
+    let mut next = 0;
+    scenario.expect(
+        handle
+            .token()
+            .and_return_with(move || {
+                next += 1;
+                Token(next)
+            })
+            .times(2),
+    );
+
+    assert_eq!(mock.token(), Token(1));
+    assert_eq!(mock.token(), Token(2));
+}
+
+#[test]
+fn test_return_err_with() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Connection>();
+
+    scenario.expect(
+        handle
+            .send(ANY)
+            .and_return_err_with(|| io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            .times(2),
+    );
+
+    assert_eq!(mock.send(b"a").unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+    assert_eq!(mock.send(b"b").unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+}
+
+#[test]
+fn test_return_ok_with() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Connection>();
+
+    scenario.expect(handle.receive().and_return_ok_with(|| Token(7)).times(2));
+
+    assert_eq!(mock.receive(), Ok(Token(7)));
+    assert_eq!(mock.receive(), Ok(Token(7)));
+}
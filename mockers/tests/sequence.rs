@@ -0,0 +1,127 @@
+//! Test partial ordering of expectations with sequences.
+use mockers_derive::mocked;
+
+use mockers::matchers::ANY;
+use mockers::{Scenario, Sequence};
+
+#[mocked]
+pub trait A {
+    fn foo(&self);
+    fn bar(&self, arg: u32);
+    fn baz(&self);
+}
+
+#[mocked]
+pub trait B {
+    fn qux(&self);
+}
+
+#[test]
+fn test_open_cardinality_in_sequence() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    let mut seq = Sequence::new();
+    seq.expect(handle.foo().and_return_clone(()).times(0..));
+    seq.expect(handle.bar(4).and_return(()));
+    scenario.expect(seq);
+
+    mock.bar(4);
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `A#0.foo()`")]
+fn test_prerequisites_are_retired() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    let mut seq = Sequence::new();
+    seq.expect(handle.foo().and_return_clone(()).times(0..));
+    seq.expect(handle.bar(4).and_return(()));
+    scenario.expect(seq);
+
+    mock.foo();
+    mock.bar(4);
+    mock.foo();
+}
+
+#[test]
+fn test_expectation_in_several_sequences() {
+    let scenario = Scenario::new();
+    let (a, a_handle) = scenario.create_mock_for::<dyn A>();
+    let (b, b_handle) = scenario.create_mock_for::<dyn B>();
+
+    let writes = Sequence::named("writes");
+    let reads = Sequence::named("reads");
+    scenario.expect(a_handle.foo().and_return(()).in_sequence(&writes));
+    scenario.expect(b_handle.qux().and_return(()).in_sequence(&reads));
+    scenario.expect(a_handle.bar(ANY).and_return(()).in_sequence(&writes).in_sequence(&reads));
+
+    // `foo` and `qux` are not ordered relative to each other.
+    b.qux();
+    a.foo();
+    a.bar(1);
+}
+
+#[test]
+#[should_panic(expected = "call is out of order, it matches `A#0.bar(_)`, but prerequisite\n  `B#0.qux()` in sequence `reads` is not satisfied yet")]
+fn test_out_of_order_call_names_prerequisite() {
+    let scenario = Scenario::new();
+    let (a, a_handle) = scenario.create_mock_for::<dyn A>();
+    let (_b, b_handle) = scenario.create_mock_for::<dyn B>();
+
+    let writes = Sequence::named("writes");
+    let reads = Sequence::named("reads");
+    scenario.expect(a_handle.foo().and_return(()).in_sequence(&writes));
+    scenario.expect(b_handle.qux().and_return(()).in_sequence(&reads));
+    scenario.expect(a_handle.bar(ANY).and_return(()).in_sequence(&writes).in_sequence(&reads));
+
+    a.foo();
+    a.bar(1);
+}
+
+#[test]
+#[should_panic(expected = "but prerequisite\n  `A#0.foo() must be called exactly 2 times, called 1 times` is not satisfied yet")]
+fn test_out_of_order_call_reports_cardinality() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    let mut seq = Sequence::new();
+    seq.expect(handle.foo().and_return_clone(()).times(2));
+    seq.expect(handle.bar(4).and_return(()));
+    scenario.expect(seq);
+
+    mock.foo();
+    mock.bar(4);
+}
+
+#[test]
+fn test_sequence_scope() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    {
+        let _scope = scenario.in_sequence(&Sequence::new());
+        scenario.expect(handle.foo().and_return(()));
+        scenario.expect(handle.bar(4).and_return(()));
+    }
+    // Expectations created out of scope are not ordered.
+    scenario.expect(handle.baz().and_return(()));
+
+    mock.baz();
+    mock.foo();
+    mock.bar(4);
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to `A#0.bar(4)`")]
+fn test_sequence_scope_invalid_order() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    let _scope = scenario.in_sequence(&Sequence::new());
+    scenario.expect(handle.foo().and_return(()));
+    scenario.expect(handle.bar(4).and_return(()));
+
+    mock.bar(4);
+}
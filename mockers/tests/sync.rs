@@ -0,0 +1,79 @@
+///! Test that mocks may be used from other threads when `sync` feature is enabled.
+use std::sync::Arc;
+use std::thread;
+
+use mockers::matchers::ANY;
+use mockers::Scenario;
+use mockers_derive::mocked;
+
+#[mocked]
+pub trait A {
+    fn foo(&self, arg: u32) -> u32;
+}
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_mock_and_handle_are_send_sync() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+    assert_send_sync(&scenario);
+    assert_send_sync(&mock);
+    assert_send_sync(&handle);
+    assert_send_sync(&scenario.handle());
+}
+
+#[test]
+fn test_call_from_spawned_thread() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    scenario.expect(handle.foo(2).and_return(3));
+
+    let result = thread::spawn(move || mock.foo(2)).join().unwrap();
+    assert_eq!(result, 3);
+}
+
+#[test]
+fn test_call_from_several_threads() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    scenario.expect(handle.foo(ANY).and_call_clone(|arg| arg * 2).times(4));
+
+    let mock = Arc::new(mock);
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let mock = mock.clone();
+            thread::spawn(move || mock.foo(i))
+        })
+        .collect();
+    let mut results: Vec<u32> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+    results.sort();
+    assert_eq!(results, vec![0, 2, 4, 6]);
+}
+
+#[test]
+fn test_expect_from_spawned_thread() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    let scenario_handle = scenario.handle();
+    thread::spawn(move || scenario_handle.expect(handle.foo(2).and_return(3)))
+        .join()
+        .unwrap();
+
+    assert_eq!(mock.foo(2), 3);
+}
+
+#[test]
+#[should_panic(expected = "Some expectations are not satisfied:\n`A#0.foo(2)`\n")]
+fn test_unexpected_call_in_thread_does_not_poison_scenario() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn A>();
+
+    scenario.expect(handle.foo(2).and_return(3));
+
+    let result = thread::spawn(move || mock.foo(5)).join();
+    assert!(result.is_err());
+}
@@ -0,0 +1,55 @@
+//! Test mocking unsafe traits and unsafe methods.
+use mockers_derive::mocked;
+
+use mockers::matchers::ANY;
+use mockers::Scenario;
+
+#[mocked]
+trait Allocator {
+    unsafe fn alloc(&self, size: usize) -> usize;
+    unsafe fn dealloc(&self, addr: usize, size: usize);
+    unsafe fn reset();
+}
+
+/// # Safety
+///
+/// Implementors must write to device registers only.
+#[mocked]
+unsafe trait Device {
+    fn id(&self) -> u32;
+    unsafe fn write(&mut self, register: u32, value: u32);
+}
+
+fn require_device<D: Device>(device: &D) -> u32 {
+    device.id()
+}
+
+#[test]
+fn test_unsafe_methods() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<AllocatorMock>();
+    let (_mock_static, static_handle) = scenario.create_mock::<AllocatorMockStatic>();
+
+    scenario.expect(handle.alloc(16).and_return(0x1000));
+    scenario.expect(handle.dealloc(ANY, 16).and_return(()));
+    scenario.expect(static_handle.reset().and_return(()));
+
+    unsafe {
+        let addr = mock.alloc(16);
+        assert_eq!(addr, 0x1000);
+        mock.dealloc(addr, 16);
+        AllocatorMock::reset();
+    }
+}
+
+#[test]
+fn test_unsafe_trait() {
+    let scenario = Scenario::new();
+    let (mut mock, handle) = scenario.create_mock_for::<dyn Device>();
+
+    scenario.expect(handle.id().and_return(3));
+    scenario.expect(handle.write(1, 2).and_return(()));
+
+    assert_eq!(require_device(&mock), 3);
+    unsafe { mock.write(1, 2) };
+}
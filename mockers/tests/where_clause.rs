@@ -0,0 +1,124 @@
+//! Test mocking traits and methods with where clauses.
+use mockers::Scenario;
+use mockers_derive::{mock, mocked};
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+#[mocked]
+pub trait Repo<K>
+where
+    K: Hash + Eq,
+{
+    fn get(&self, key: K) -> Option<String>;
+    fn put(&self, key: K, value: String)
+    where
+        K: Display;
+    fn name(&self) -> String
+    where
+        Self: Sized;
+}
+
+#[mocked]
+pub trait Parser<'a, T>
+where
+    T: 'a,
+{
+    fn parse(&self, input: &'a str) -> T;
+}
+
+#[mocked]
+pub trait Owned
+where
+    Self: 'static,
+{
+    fn id(&self) -> u32;
+}
+
+#[mocked]
+pub trait Factory
+where
+    Self: Sized,
+{
+    fn create() -> Self;
+    fn name(&self) -> String;
+}
+
+pub trait Named {
+    fn name(&self) -> String;
+}
+
+pub trait Greeter
+where
+    Self: Named,
+{
+    fn greet(&self) -> String;
+}
+
+mock! {
+    GreeterMock,
+    self,
+    trait Named {
+        fn name(&self) -> String;
+    },
+    self,
+    trait Greeter where Self: Named {
+        fn greet(&self) -> String;
+    }
+}
+
+#[test]
+fn test_trait_where_clause() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Repo<u32>>();
+    scenario.expect(handle.get(1).and_return(Some("one".to_owned())));
+    assert_eq!(mock.get(1), Some("one".to_owned()));
+}
+
+#[test]
+fn test_method_where_clause() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Repo<u32>>();
+    scenario.expect(handle.put(1, "one".to_owned()).and_return(()));
+    scenario.expect(handle.name().and_return("repo".to_owned()));
+    mock.put(1, "one".to_owned());
+    assert_eq!(mock.name(), "repo");
+}
+
+#[test]
+fn test_where_clause_with_trait_lifetime() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Parser<u32>>();
+    scenario.expect(handle.parse("5").and_return(5));
+    assert_eq!(mock.parse("5"), 5);
+}
+
+#[test]
+fn test_self_bound() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock_for::<dyn Owned>();
+    scenario.expect(handle.id().and_return(7));
+    assert_eq!(mock.id(), 7);
+}
+
+#[test]
+fn test_sized_trait() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<FactoryMock>();
+    let (_mock_static, static_handle) = scenario.create_mock::<FactoryMockStatic>();
+    scenario.expect(static_handle.create().and_return(mock));
+    scenario.expect(handle.name().and_return("created".to_owned()));
+
+    let factory = FactoryMock::create();
+    assert_eq!(factory.name(), "created");
+}
+
+#[test]
+fn test_self_bounded_by_base_trait() {
+    let scenario = Scenario::new();
+    let (mock, handle) = scenario.create_mock::<GreeterMock>();
+    scenario.expect(handle.name().and_return("world".to_owned()));
+    scenario.expect(handle.greet().and_return("hello".to_owned()));
+    assert_eq!(mock.name(), "world");
+    assert_eq!(mock.greet(), "hello");
+}
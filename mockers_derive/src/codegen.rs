@@ -1,25 +1,27 @@
 use itertools::Itertools;
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
 use proc_quote::{quote, ToTokens};
 use std::collections::{HashMap, HashSet};
 use std::result::Result;
 use std::sync::Mutex;
 use syn::{
-    parse_quote, punctuated::Punctuated,  ArgCaptured, Expr, FnArg, FnDecl, ForeignItem,
-    ForeignItemFn, GenericParam, Generics, Ident, ImplItemType, Item, ItemImpl, ItemStruct,
-    ItemTrait, Lifetime, Pat, PatIdent, Path, PathArguments, PathSegment, ReturnType, Token,
-    TraitBound, TraitBoundModifier, TraitItem, TraitItemMethod, TraitItemType, Type, TypeParam,
-    TypeParamBound, TypeReference,
+    parse_quote, punctuated::Punctuated,  ArgCaptured, ArgSelfRef, Expr, FnArg, FnDecl, ForeignItem,
+    ForeignItemFn, GenericParam, Generics, Ident, Item, ItemImpl, ItemStruct,
+    ItemTrait, Lifetime, LifetimeDef, Lit, Pat, PatIdent, Path, PathArguments, PathSegment,
+    PredicateType, ReturnType, Token, TraitBound, TraitBoundModifier, TraitItem, TraitItemConst,
+    TraitItemMethod, TraitItemType, Type, TypeParam, TypeParamBound, TypeReference, WhereClause,
+    WherePredicate,
 };
 
-use crate::options::{parse_macro_args, MockAttrOptions, TraitDesc, DerivedTraits, DeriveClone, Location};
-use crate::type_manip::{qualify_self, set_self};
+use crate::options::{parse_macro_args, MockAttrOptions, TraitDesc, DerivedTraits, DeriveClone, Location, RecordArgs};
+use crate::type_manip::{boxed_future_output, has_impl_trait, is_send_boxed_future, mentions_self_type, qualify_self, set_self};
 use crate::error::{self, Error};
 use crate::id_gen::IdGen;
 #[cfg(feature="debug")] use crate::debug::format_code;
 use crate::util::is_path_absolute;
 
 use std::iter::FromIterator as _;
+use syn::parse::Parse;
 use syn::spanned::Spanned as _;
 
 lazy_static! {
@@ -38,6 +40,10 @@ lazy_static! {
     static ref REGISTERED_TYPE_ID_GENERATOR: IdGen = IdGen::new();
 }
 
+/// Maximum number of mocked method arguments (not counting `self`).
+/// It must be in sync with number of `CallMatchN` and `verifyN` defined in `mockers`.
+const MAX_ARGS: usize = 12;
+
 pub fn mocked_impl(input: TokenStream, opts_span: Span, opts: &MockAttrOptions) -> Result<TokenStream, Error> {
     let mut result = input.clone();
     let source_item: Item = syn::parse2(input)?;
@@ -109,7 +115,7 @@ fn generate_mock(span: Span, item: &Item, opts_span: Span, opts: &MockAttrOption
             })?;
             let handle_name = Ident::new(&format!("{}Handle", mock_name), Span::call_site());
 
-            Ok((generate_extern_mock(foreign_mod, mock_name, &handle_name)?, false))
+            Ok((generate_extern_mock(foreign_mod, mock_name, &handle_name, &opts.record_args)?, false))
         }
         _ => Err(Error::Spanned(span, error::ERR_TRAITS_AND_EXTERN_ONLY_ARE_SUPPORTED.to_string())),
     }
@@ -157,7 +163,8 @@ fn generate_trait_mock(
     let mut all_traits = referenced_items;
     all_traits.push(trait_desc);
 
-    generate_mock_for_traits(mock_ident, handle_ident, &all_traits, !opts.location.is_extern(), &opts.derives)
+    generate_mock_for_traits(mock_ident, handle_ident, &all_traits, !opts.location.is_extern(), &opts.derives,
+                             &opts.consts, &opts.record_args)
 }
 
 /// Find supertrait definitions.
@@ -172,14 +179,13 @@ fn generate_trait_mock(
 fn find_referenced_supertraits(item: &ItemTrait, refs: &HashMap<Path, Path>)
         -> Result<Vec<TraitDesc>, Error> {
     item.supertraits.iter()
-        .map(|b| {
+        .filter_map(|b| match *b {
+            // Lifetime bounds don't refer to other traits.
+            TypeParamBound::Lifetime(..) => None,
+            TypeParamBound::Trait(TraitBound { ref path, .. }) => Some((b, path)),
+        })
+        .map(|(b, path)| {
             // Reference to supertrait as given in trait definition: `B` in `trait A: B { .. }`
-            let path = match *b {
-                TypeParamBound::Lifetime(ref l) => {
-                    return Err(Error::Spanned(l.span(), error::ERR_LIFETIME_BOUNDS_NOT_SUPPORTED.to_string()));
-                }
-                TypeParamBound::Trait(TraitBound { ref path, .. }) => path,
-            };
 
             // If absolute path to supertrait is given (`trait A: ::module::B`),
             // it is used as is. Otherwise supertrait reference must be explained in
@@ -234,27 +240,32 @@ fn generate_mock_for_traits(
     trait_items: &[TraitDesc],
     local: bool,
     derives: &DerivedTraits,
+    consts: &HashMap<Ident, Lit>,
+    record_args: &RecordArgs,
 ) -> Result<TokenStream, Error> {
     // Validate items, reject unsupported ones.
     let mut trait_paths = HashSet::<String>::new();
+    // Lifetime bounds of all traits, like `'static` in `trait A: 'static`,
+    // mock must satisfy them too.
+    let mut lifetime_bounds = Vec::<Lifetime>::new();
+    // Where clause predicates of all traits, mock must satisfy them too.
+    let mut where_predicates = Vec::<WherePredicate>::new();
     let traits: Vec<(Path, &Vec<TraitItem>)> = trait_items
         .iter()
         .map(|desc| {
-            let ItemTrait { unsafety, ref generics, ref supertraits, ref items, .. } = desc.trait_item;
-            if let Some(unsafety) = unsafety {
-                return Err(Error::Spanned(unsafety.span(), error::ERR_UNSAFE_TRAITS_NOT_SUPPORTED.to_string()));
-            }
-
-            if let Some(lt) = generics.lifetimes().next() {
-                return Err(Error::Spanned(lt.span(), error::ERR_LIFETIME_PARAMS_NOT_SUPPORTED.to_string()));
-            }
-
+            let ItemTrait { ref generics, ref supertraits, ref items, .. } = desc.trait_item;
             if let Some(cp) = generics.const_params().next() {
                 return Err(Error::Spanned(cp.span(), error::ERR_CONST_PARAMS_NOT_SUPPORTED.to_string()));
             }
 
             if let Some(ref where_clause) = generics.where_clause {
-                return Err(Error::Spanned(where_clause.where_token.span(), error::ERR_WHERE_CLAUSES_NOT_SUPPORTED.to_string()));
+                // `where Self: B` is the same as `trait A: B`, so definition of `B` is required too.
+                if let Some(path) = where_clause.predicates.iter().flat_map(self_trait_bounds)
+                        .find(|path| !trait_paths.contains(&path.into_token_stream().to_string())) {
+                    return Err(Error::Spanned(path.span(), error::ERR_NO_BASE_TRAIT_DEFINITIONS.to_string()));
+                }
+                // Mock implements base traits itself, so bounds by them are dropped.
+                where_predicates.extend(where_clause.predicates.iter().filter_map(without_self_trait_bounds));
             }
 
             for bound in supertraits {
@@ -283,8 +294,8 @@ fn generate_mock_for_traits(
                             }
                         }
                     }
-                    TypeParamBound::Lifetime(..) => {
-                        return Err(Error::General(error::ERR_LIFETIME_PARAM_BOUNDS_NOT_SUPPORTED.to_string()));
+                    TypeParamBound::Lifetime(ref lifetime) => {
+                        lifetime_bounds.push(lifetime.clone());
                     }
                 }
             }
@@ -300,9 +311,30 @@ fn generate_mock_for_traits(
         })
         .collect::<Result<Vec<(Path, &Vec<TraitItem>)>, Error>>()?;
 
-    // Extract type parameters from root trait only (which is last)
-    // This is [`B`, `C`] in `trait A<B, C> { .. }`.
+    // All constants given in attribute must be declared by traits.
+    for name in consts.keys() {
+        let declared = Itertools::flatten(traits.iter().map(|&(_, members)| members.iter()))
+            .any(|member| matches!(member, TraitItem::Const(TraitItemConst { ref ident, .. }) if ident == name));
+        if !declared {
+            return Err(Error::Spanned(name.span(), error::ERR_UNKNOWN_CONST.to_string()));
+        }
+    }
+
+    // All methods listed in `record_args` must be declared by traits.
+    if let RecordArgs::Methods(ref methods) = *record_args {
+        for name in methods {
+            let declared = Itertools::flatten(traits.iter().map(|&(_, members)| members.iter()))
+                .any(|member| matches!(member, TraitItem::Method(TraitItemMethod { ref sig, .. }) if sig.ident == *name));
+            if !declared {
+                return Err(Error::Spanned(name.span(), error::ERR_UNKNOWN_RECORDED_METHOD.to_string()));
+            }
+        }
+    }
+
+    // Extract lifetime and type parameters from root trait only (which is last)
+    // This is [`'a`] and [`B`, `C`] in `trait A<'a, B, C> { .. }`.
     // TODO: specify root trait explicitly
+    let mut lifetimes: Vec<LifetimeDef> = Vec::new();
     let mut type_params: Vec<TypeParam> = Vec::new();
     for gen in &trait_items.last().as_ref().unwrap().trait_item.generics.params {
         match gen {
@@ -310,14 +342,23 @@ fn generate_mock_for_traits(
                 return Err(Error::Spanned(c.span(), error::ERR_CONST_PARAMS_NOT_SUPPORTED.to_string()));
             }
             GenericParam::Lifetime(l) => {
-                return Err(Error::Spanned(l.span(), error::ERR_LIFETIME_PARAMS_NOT_SUPPORTED.to_string()));
+                lifetimes.push(l.clone());
             }
             GenericParam::Type(t) => {
-                type_params.push(validate_type_param_bounds(t)?.clone());
+                type_params.push(validate_type_param_bounds(t, &lifetimes)?.clone());
             }
         }
     }
 
+    // Where clause used for trait implementation, it is copied as is.
+    let trait_where_clause = make_where_clause(where_predicates.iter().cloned());
+    // Where clause used for mock structs and all other impls. `Self` means
+    // something else there, so predicates mentioning it are dropped.
+    let where_clause = make_where_clause(where_predicates.iter().filter(|p| !mentions_self(p)).cloned());
+    // Traits requiring `Self: Sized` can't be made into objects.
+    let requires_sized = where_predicates.iter().any(bounds_self_with_sized);
+
+    let lifetime_idents = &lifetimes.iter().map(|l| l.lifetime.clone()).collect::<Vec<_>>();
     let type_param_idents = type_params.iter().map(|t| t.ident.clone()).collect::<Vec<_>>();
 
     // Gather associated types from all traits, because they are used in mock
@@ -327,7 +368,7 @@ fn generate_mock_for_traits(
     for &(_, ref members) in &traits {
         for member in members.iter() {
             if let TraitItem::Type(assoc_type) = member {
-                assoc_types2.push(validate_trait_item_type_bounds(assoc_type)?.clone());
+                assoc_types2.push(validate_trait_item_type_bounds(assoc_type, &lifetimes)?.clone());
             }
         }
     }
@@ -341,60 +382,77 @@ fn generate_mock_for_traits(
 
     let mock_type_param_idents = mock_type_params.iter().map(|t| t.ident.clone()).collect::<Vec<_>>();
 
+    // Mock may forward calls to real implementation boxed as trait object of root trait,
+    // if all methods may be called on such object.
+    let delegate_type = if local && lifetimes.is_empty() && !requires_sized && can_delegate(trait_items) {
+        let (ref trait_path, _) = traits[traits.len() - 1];
+        Some(quote! {
+            ::mockers::delegate_type!(#trait_path<#(#type_param_idents, )* #(#assoc_types=#assoc_types),*>)
+        })
+    } else {
+        None
+    };
+
     assert_ne!(mock_ident, handle_ident);
-    let struct_item = generate_mock_struct(&mock_ident, &mock_type_params);
-    let handle_struct_item = generate_mock_struct(&handle_ident, &mock_type_params);
+    let struct_item =
+        generate_mock_struct(&mock_ident, &lifetimes, &mock_type_params, &where_clause, delegate_type.as_ref());
+    let handle_struct_item = generate_mock_struct(&handle_ident, &lifetimes, &mock_type_params, &where_clause, None);
 
     // Generic parameters used for impls. It is part inside angles in
-    // `impl<A: ::std::fmt::Debug, B: ::std::fmt::Debug, ...> ...`.
+    // `impl<'a, A: ::std::fmt::Debug, B: ::std::fmt::Debug, ...> ...`.
+    // Lifetime bounds of traits are added to all parameters, so that mock satisfies them.
     let generics = {
         let mut gen = Generics::default();
-        gen.params = mock_type_params
+        gen.params = lifetimes
             .iter()
             .cloned()
-            .map(|param| -> GenericParam {
-                let mut param = param.clone();
+            .map(|mut param| -> GenericParam {
+                let lifetime = param.lifetime.clone();
+                param.bounds.extend(lifetime_bounds.iter().filter(|&b| *b != lifetime).cloned());
+                GenericParam::Lifetime(param)
+            })
+            .chain(mock_type_params.iter().cloned().map(|mut param| -> GenericParam {
                 param.bounds.push(parse_quote! { ::std::fmt::Debug });
+                param.bounds.extend(lifetime_bounds.iter().cloned().map(TypeParamBound::Lifetime));
                 GenericParam::Type(param)
-            })
+            }))
             .collect();
         gen
     };
 
-    // Types of mock and handle structs with all type parameters specified:
-    // `AMock<A, B>`.
+    // Types of mock and handle structs with all lifetime and type parameters specified:
+    // `AMock<'a, A, B>`.
     let struct_path: Path = {
         let mock_type_param_idents = &mock_type_param_idents;
-        parse_quote! { #mock_ident<#(#mock_type_param_idents),*> }
+        parse_quote! { #mock_ident<#(#lifetime_idents,)* #(#mock_type_param_idents),*> }
     };
     let handle_path: Path = {
         let mock_type_param_idents = &mock_type_param_idents;
-        parse_quote! { #handle_ident<#(#mock_type_param_idents),*> }
+        parse_quote! { #handle_ident<#(#lifetime_idents,)* #(#mock_type_param_idents),*> }
     };
     let struct_type: Type = parse_quote! { #struct_path };
     let handle_type: Type = parse_quote! { #handle_path };
 
     let mut generated_items = vec![struct_item, handle_struct_item];
     let mut has_static_methods = false;
+    let mut has_consts = false;
     let mut mock_type_ids = Punctuated::<usize, Token![,]>::new();
 
-    for &(ref trait_path, ref members) in &traits {
+    for (desc, &(ref trait_path, ref members)) in trait_items.iter().zip(&traits) {
         let mut impl_methods = Vec::new();
         let mut trait_impl_methods = Vec::new();
 
         let mut static_impl_methods = Vec::new();
         let mut static_trait_impl_methods = Vec::new();
 
+        let mut trait_impl_consts = Vec::new();
+
         let mock_type_id = MOCK_TYPE_ID_GENERATOR.next_id();
         mock_type_ids.push(mock_type_id);
 
         for member in members.iter() {
             match member {
                 TraitItem::Method(TraitItemMethod { ref sig, .. }) => {
-                    if let Some(unsafety) = sig.unsafety {
-                        return Err(Error::Spanned(unsafety.span(), error::ERR_UNSAFE_TRAIT_METHODS_NOT_SUPPORTED.to_string()));
-                    }
-
                     // Trait methods may not be const.
                     assert!(sig.constness.is_none());
 
@@ -402,15 +460,21 @@ fn generate_mock_for_traits(
                         return Err(Error::Spanned(abi.span(), error::ERR_EXTERN_METHODS_NOT_SUPPORTED.to_string()));
                     }
 
+                    validate_arg_count(&sig.ident, &sig.decl)?;
+
                     let trait_path_with_params = {
                         let type_param_idents = type_param_idents.iter();
-                        parse_quote! { #trait_path<#(#type_param_idents),*> }
+                        parse_quote! { #trait_path<#(#lifetime_idents,)* #(#type_param_idents),*> }
                     };
                     let methods = generate_trait_methods(
                         sig.ident.clone(),
                         &sig.decl,
+                        sig.unsafety.is_some(),
+                        sig.asyncness.is_some(),
                         &sig.decl.generics,
                         &trait_path_with_params,
+                        delegate_type.is_some(),
+                        record_args.includes(&sig.ident),
                         mock_type_id,
                         &struct_path,
                     )?;
@@ -422,13 +486,20 @@ fn generate_mock_for_traits(
                         trait_impl_methods.push(methods.trait_impl_method);
                     }
                 }
-                TraitItem::Type(TraitItemType { ref bounds, .. }) => {
-                    if !bounds.is_empty() {
-                        return Err(Error::General(error::ERR_ASSOCIATED_TYPE_BOUNDS_NOT_SUPPORTED.to_string()));
-                    }
+                TraitItem::Type(..) => {
+                    // Associated types are turned into mock type parameters.
                 }
-                TraitItem::Const(..) => {
-                    return Err(Error::General(error::ERR_TRAIT_CONST_NOT_SUPPORTED.to_string()));
+                TraitItem::Const(TraitItemConst { ref ident, ref ty, ref default, .. }) => {
+                    has_consts = true;
+                    // Value given in attribute overrides trait default one.
+                    // If it isn't given, default value is used by not implementing constant at all.
+                    match consts.get(ident) {
+                        Some(value) => trait_impl_consts.push(quote! { const #ident: #ty = #value; }),
+                        None if default.is_some() => {}
+                        None => {
+                            return Err(Error::Spanned(ident.span(), error::ERR_NO_CONST_VALUE.to_string()));
+                        }
+                    }
                 }
                 TraitItem::Macro(..) => {
                     return Err(Error::General(error::ERR_TRAIT_MACROS_NOT_SUPPORTED.to_string()));
@@ -441,24 +512,30 @@ fn generate_mock_for_traits(
 
         // `impl<...> AMockHandle<...> { pub fn foo(...) { ... } }`
         let impl_item = quote! {
-            impl #generics #handle_type {
+            impl #generics #handle_type #where_clause {
                 #(#impl_methods)*
             }
         };
 
-        // `impl<...> A for AMock<...> { ... }`
+        // `impl<...> A for AMock<...> { ... }`, unsafe traits get `unsafe impl`.
+        let unsafety = desc.trait_item.unsafety;
         let trait_impl_items = trait_impl_methods;
-        let trait_type_items = assoc_types
+        // Generic associated types are implemented with the same type
+        // for all lifetimes: `type Iter<'a> = Iter where Self: 'a;`.
+        let trait_type_items = assoc_types2
             .iter()
-            .cloned()
-            .zip(mock_type_param_idents.iter().cloned())
-            .map(|(assoc, param)| -> ImplItemType {
-                let path: Path = parse_quote! { #param };
-                parse_quote! { type #assoc = #path; }
+            .zip(mock_type_param_idents.iter().skip(type_params.len()).cloned())
+            .map(|(assoc, param)| {
+                // Built with `quote!`, because syn expects where clause before type.
+                let TraitItemType { ref ident, ref generics, .. } = *assoc;
+                let where_clause = &generics.where_clause;
+                quote! { type #ident #generics = #param #where_clause; }
             });
         let trait_impl_item = quote! {
-            impl #generics #trait_path<#(#type_param_idents),*> for #struct_type {
+            #unsafety impl #generics #trait_path<#(#lifetime_idents,)* #(#type_param_idents),*> for #struct_type
+                    #trait_where_clause {
                 #(#trait_type_items)*
+                #(#trait_impl_consts)*
                 #(#trait_impl_items)*
                 #(#static_trait_impl_methods)*
             }
@@ -472,16 +549,22 @@ fn generate_mock_for_traits(
 
             let static_mock_name = format!("{}Static", mock_ident);
             let static_mock_ident = Ident::new(&static_mock_name.clone(), Span::call_site());
-            let static_struct_item = generate_mock_struct(&static_mock_ident, &mock_type_params);
+            let static_struct_item =
+                generate_mock_struct(&static_mock_ident, &lifetimes, &mock_type_params, &where_clause, None);
 
             let static_handle_name = format!("{}StaticHandle", mock_ident);
             let static_handle_ident = Ident::new(&static_handle_name.clone(), Span::call_site());
-            let static_handle_struct_item = generate_mock_struct(&static_handle_ident, &mock_type_params);
-            let static_handle_impl = generate_handle_impl(&static_handle_ident, &mock_type_params);
-            let static_handle_struct_type: Type = parse_quote! { #static_handle_ident<#(assoc_types),*> };
+            let static_handle_struct_item =
+                generate_mock_struct(&static_handle_ident, &lifetimes, &mock_type_params, &where_clause, None);
+            let static_handle_impl =
+                generate_handle_impl(&static_handle_ident, &lifetimes, &mock_type_params, &where_clause);
+            let static_handle_struct_type: Type = {
+                let mock_type_param_idents = &mock_type_param_idents;
+                parse_quote! { #static_handle_ident<#(#lifetime_idents,)* #(#mock_type_param_idents),*> }
+            };
             // `impl<...> AMockStaticHandle<...> { pub fn foo(...) { ... } }`
             let static_handle_impl_item = quote! {
-                impl #generics #static_handle_struct_type {
+                impl #generics #static_handle_struct_type #where_clause {
                     #(#static_impl_methods)*
                 }
             };
@@ -501,8 +584,11 @@ fn generate_mock_for_traits(
                 &static_mock_ident,
                 &static_handle_ident,
                 &static_mock_name,
+                &lifetimes,
                 &mock_type_params,
+                &where_clause,
                 &custom_init_code,
+                false,
             );
 
             generated_items.push(static_struct_item);
@@ -523,17 +609,26 @@ fn generate_mock_for_traits(
         })
         .join("+");
 
-    let mock_impl_item =
-        generate_mock_impl(&mock_ident, &handle_ident, &mocked_class_name, &mock_type_params, &quote! {});
+    let mock_impl_item = generate_mock_impl(
+        &mock_ident,
+        &handle_ident,
+        &mocked_class_name,
+        &lifetimes,
+        &mock_type_params,
+        &where_clause,
+        &quote! {},
+        delegate_type.is_some(),
+    );
     generated_items.push(mock_impl_item);
 
-    let handle_impl_item = generate_handle_impl(&handle_ident, &mock_type_params);
+
+    let handle_impl_item = generate_handle_impl(&handle_ident, &lifetimes, &mock_type_params, &where_clause);
     generated_items.push(handle_impl_item);
 
     let debug_impl_item = quote! {
-        impl<#(#mock_type_params),*> ::std::fmt::Debug for #mock_ident<#(#mock_type_param_idents),*> {
+        impl<#(#lifetimes,)* #(#mock_type_params),*> ::std::fmt::Debug for #struct_type #where_clause {
             fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
-                f.write_str(self.scenario.borrow().get_mock_name(self.mock_id))
+                f.write_str(::mockers::shared::lock(&self.scenario).get_mock_name(self.mock_id))
             }
         }
     };
@@ -541,66 +636,161 @@ fn generate_mock_for_traits(
 
     let has_generic_method = Itertools::flatten(traits.iter().map(|&(_, members)| members.iter()))
         .any(|member| match member {
-            TraitItem::Method(TraitItemMethod { ref sig, .. }) => {
-                !sig.decl.generics.params.is_empty()
-            }
+            TraitItem::Method(TraitItemMethod { ref sig, .. }) => has_type_params(&sig.decl.generics),
+            _ => false,
+        });
+    // Traits with `async fn` methods can't be made into objects.
+    let has_async_fn = Itertools::flatten(traits.iter().map(|&(_, members)| members.iter()))
+        .any(|member| match member {
+            TraitItem::Method(TraitItemMethod { ref sig, .. }) => sig.asyncness.is_some(),
             _ => false,
         });
-    if local && !has_generic_method && !has_static_methods {
+    // Traits with associated constants or generic associated types can't be made into objects either.
+    let has_generic_assoc_type = assoc_types2.iter().any(|t| !t.generics.params.is_empty());
+    if local && !has_generic_method && !has_static_methods && !has_async_fn && !requires_sized && !has_consts
+            && !has_generic_assoc_type {
         let (ref trait_path, _) = traits[traits.len() - 1];
 
         // Create path for trait being mocked. Path includes bindings for all associated types.
-        // Generated impl example:
+        // Trait object is referenced with `'static` lifetime, so all lifetime parameters
+        // are `'static` too. Generated impl example:
         //
-        //     impl<Item, B> ::mockers::Mocked for &'static A<B, Item=Item> {
-        //         type MockImpl = AMock<Item, B>;
+        //     impl<Item, B> ::mockers::Mocked for &'static A<'static, B, Item=Item> {
+        //         type MockImpl = AMock<'static, Item, B>;
         //     }
+        let static_lifetimes = lifetimes.iter().map(|_| quote! { 'static }).collect::<Vec<_>>();
+        let static_where_clause = where_clause.as_ref().map(|c| make_lifetimes_static(c, lifetime_idents));
+        let static_type_params = mock_type_params.iter().map(|p| make_lifetimes_static(p, lifetime_idents)).collect::<Vec<_>>();
         let mocked_impl_item = quote! {
-            impl<#(#mock_type_params),*> ::mockers::Mocked
-                for &'static #trait_path<#(#type_param_idents, )* #(#assoc_types=#assoc_types),*> {
-                type MockImpl = #mock_ident<#(#mock_type_param_idents),*>;
+            impl<#(#static_type_params),*> ::mockers::Mocked
+                for &'static #trait_path<#(#static_lifetimes, )* #(#type_param_idents, )* #(#assoc_types=#assoc_types),*>
+                #static_where_clause {
+                type MockImpl = #mock_ident<#(#static_lifetimes, )* #(#mock_type_param_idents),*>;
             }
         };
 
-        generated_items.push(mocked_impl_item)
+        generated_items.push(mocked_impl_item);
+
+        if let Some(ref delegate_type) = delegate_type {
+            let delegating_impl_item = quote! {
+                impl<#(#mock_type_params),*> ::mockers::Delegating
+                    for &'static #trait_path<#(#type_param_idents, )* #(#assoc_types=#assoc_types),*>
+                    #where_clause {
+                    type Delegate = #delegate_type;
+
+                    fn set_delegate(mock: &mut Self::MockImpl, delegate: Box<Self::Delegate>) {
+                        mock.delegate = Some(delegate);
+                    }
+                }
+            };
+            generated_items.push(delegating_impl_item);
+        }
     }
 
-    generated_items.extend(derive_standard_traits(derives, &mock_ident, &handle_ident, &type_params));
+    generated_items.extend(derive_standard_traits(
+        derives, &mock_ident, &handle_ident, &lifetimes, &type_params, &where_clause));
 
     Ok(quote! { #(#generated_items)* })
 }
 
 /// Create mock structure. Structure is quite simple and basically contains only reference
 /// to scenario and own ID.
-/// Since type parameters are unused, we have to use PhantomData for them.
-fn generate_mock_struct(mock_ident: &Ident, type_params: &[TypeParam]) -> TokenStream {
-    let type_param_idents = type_params.iter().map(|t| t.ident.clone()).collect::<Vec<_>>();
+/// Since lifetime and type parameters are unused, we have to use PhantomData for them.
+/// Mocks which may forward calls to real implementation also contain it.
+fn generate_mock_struct(mock_ident: &Ident, lifetimes: &[LifetimeDef], type_params: &[TypeParam],
+                        where_clause: &Option<WhereClause>, delegate_type: Option<&TokenStream>)
+        -> TokenStream {
+    let phantom_types = lifetimes
+        .iter()
+        .map(|l| {
+            let lifetime = &l.lifetime;
+            quote! { &#lifetime () }
+        })
+        .chain(type_params.iter().map(|t| t.ident.clone().into_token_stream()))
+        .collect::<Vec<_>>();
+    let delegate_field = delegate_type.map(|delegate_type| quote! {
+        delegate: Option<Box<#delegate_type>>,
+    });
     quote! {
-        pub struct #mock_ident<#(#type_params),*> {
-            scenario: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>,
+        pub struct #mock_ident<#(#lifetimes,)* #(#type_params),*> #where_clause {
+            scenario: ::mockers::ScenarioRef,
             mock_id: usize,
-            _phantom_data: ::std::marker::PhantomData<(#(#type_param_idents),*)>,
+            #delegate_field
+            _phantom_data: ::std::marker::PhantomData<(#(#phantom_types),*)>,
         }
     }
 }
 
+/// Checks whether calls to mock may be forwarded to real implementation
+/// boxed as trait object of root trait. All methods must be callable on trait object
+/// and all other traits must be supertraits of root one.
+fn can_delegate(trait_items: &[TraitDesc]) -> bool {
+    let supertraits = trait_items
+        .iter()
+        .flat_map(|desc| desc.trait_item.supertraits.iter())
+        .flat_map(|bound| match *bound {
+            TypeParamBound::Trait(TraitBound { ref path, .. }) => path.segments.last().map(|s| s.value().ident.clone()),
+            TypeParamBound::Lifetime(..) => None,
+        })
+        .collect::<HashSet<_>>();
+    let (root, bases) = trait_items.split_last().unwrap();
+    if !bases.iter().all(|desc| supertraits.contains(&desc.trait_item.ident)) {
+        return false;
+    }
+
+    bases.iter().chain(Some(root)).flat_map(|desc| desc.trait_item.items.iter()).all(|item| match item {
+        TraitItem::Method(TraitItemMethod { ref sig, .. }) => {
+            let receiver_is_ref = matches!(sig.decl.inputs.iter().next(), Some(FnArg::SelfRef(..)));
+            let args_mention_self = sig.decl.inputs.iter().any(|arg| match *arg {
+                FnArg::Captured(ArgCaptured { ref ty, .. }) => mentions_self_type(ty),
+                _ => false,
+            });
+            let output_mentions_self = match sig.decl.output {
+                ReturnType::Type(_, ref ty) => mentions_self_type(ty),
+                ReturnType::Default => false,
+            };
+            receiver_is_ref
+                && sig.asyncness.is_none()
+                && !has_type_params(&sig.decl.generics)
+                && !where_mentions_self(&sig.decl.generics)
+                && !args_mention_self
+                && !output_mentions_self
+        }
+        TraitItem::Type(TraitItemType { ref generics, .. }) => generics.params.is_empty(),
+        TraitItem::Const(..) => false,
+        _ => true,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 fn generate_mock_impl(
     mock_ident: &Ident,
     handle_ident: &Ident,
     mocked_class_name: &str,
+    lifetimes: &[LifetimeDef],
     associated_types: &[TypeParam],
+    where_clause: &Option<WhereClause>,
     custom_init_code: &TokenStream,
+    has_delegate: bool,
 ) -> TokenStream {
+    let lifetime_idents = lifetimes.iter().map(|l| l.lifetime.clone()).collect::<Vec<_>>();
     let associated_type_idents = associated_types.iter().map(|t| t.ident.clone()).collect::<Vec<_>>();
+    let delegate_init = if has_delegate {
+        Some(quote! { delegate: None, })
+    } else {
+        None
+    };
     quote! {
-        impl<#(#associated_types),*> ::mockers::Mock for #mock_ident<#(#associated_type_idents),*> {
-            type Handle = #handle_ident<#(#associated_type_idents),*>;
+        impl<#(#lifetimes,)* #(#associated_types),*> ::mockers::Mock
+                for #mock_ident<#(#lifetime_idents,)* #(#associated_type_idents),*> #where_clause {
+            type Handle = #handle_ident<#(#lifetime_idents,)* #(#associated_type_idents),*>;
 
-            fn new(id: usize, scenario_int: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>) -> Self {
+            fn new(id: usize, scenario_int: ::mockers::ScenarioRef) -> Self {
                 #custom_init_code
                 #mock_ident {
                     scenario: scenario_int,
                     mock_id: id,
+                    #delegate_init
                     _phantom_data: ::std::marker::PhantomData,
                 }
             }
@@ -614,12 +804,16 @@ fn generate_mock_impl(
 
 fn generate_handle_impl(
     handle_ident: &Ident,
+    lifetimes: &[LifetimeDef],
     associated_types: &[TypeParam],
+    where_clause: &Option<WhereClause>,
 ) -> TokenStream {
+    let lifetime_idents = lifetimes.iter().map(|l| l.lifetime.clone()).collect::<Vec<_>>();
     let associated_type_idents = associated_types.iter().map(|t| t.ident.clone()).collect::<Vec<_>>();
     quote! {
-        impl<#(#associated_types),*> ::mockers::MockHandle for #handle_ident<#(#associated_type_idents),*> {
-            fn new(id: usize, scenario_int: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>) -> Self {
+        impl<#(#lifetimes,)* #(#associated_types),*> ::mockers::MockHandle
+                for #handle_ident<#(#lifetime_idents,)* #(#associated_type_idents),*> #where_clause {
+            fn new(id: usize, scenario_int: ::mockers::ScenarioRef) -> Self {
                 #handle_ident {
                     scenario: scenario_int,
                     mock_id: id,
@@ -637,11 +831,16 @@ struct GeneratedMethods {
     is_static: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_trait_methods(
     method_ident: Ident,
     decl: &FnDecl,
+    is_unsafe: bool,
+    is_async: bool,
     generics: &Generics,
     trait_path: &Path,
+    has_delegate: bool,
+    record_args: bool,
     mock_type_id: usize,
     mock_struct_path: &Path,
 ) -> Result<GeneratedMethods, String> {
@@ -666,6 +865,7 @@ fn generate_trait_methods(
             &generics,
             &decl.inputs,
             &adjusted_return_type,
+            is_async,
         )?;
 
         let get_info_expr = quote! {
@@ -678,10 +878,13 @@ fn generate_trait_methods(
             &method_ident,
             &generics,
             None,
+            None,
             get_info_expr,
             &decl.inputs,
             &adjusted_return_type,
-            false,
+            is_unsafe,
+            is_async,
+            record_args,
         )?;
 
         return Ok(GeneratedMethods {
@@ -699,9 +902,13 @@ fn generate_trait_methods(
         mock_type_id,
         method_ident.clone(),
         generics,
+        has_delegate,
         self_arg,
         &args,
         &return_type,
+        is_unsafe,
+        is_async,
+        record_args,
     )?;
     let impl_method = generate_impl_method_for_trait(
         mock_type_id,
@@ -709,6 +916,7 @@ fn generate_trait_methods(
         generics,
         &args,
         &return_type,
+        is_async,
         trait_path,
         mock_struct_path,
     )?;
@@ -738,42 +946,85 @@ fn generate_trait_methods(
 ///         ::mockers::MethodData{mock_id: self.mock_id,
 ///                               mock_type_id: 15usize,
 ///                               method_name: "method",
-///                               type_param_ids: vec![] };
-///     let action = self.scenario.borrow_mut().verify2(method_data, foo, bar);
+///                               type_param_ids: vec![],
+///                               default_result: Some(..),
+///                               record_args: None };
+///     let action = ::mockers::shared::lock(&self.scenario).verify2(method_data, foo, bar);
 ///     action()
 /// }
 /// ```
 /// where constant marked with `mock_id` is unique trait method ID.
+#[allow(clippy::too_many_arguments)]
 fn generate_trait_impl_method(
     mock_type_id: usize,
     method_ident: Ident,
     generics: &Generics,
+    has_delegate: bool,
     self_arg: &FnArg,
     args: &Punctuated<FnArg, Token![,]>,
     return_type: &Type,
+    is_unsafe: bool,
+    is_async: bool,
+    record_args: bool,
 ) -> Result<TokenStream, String> {
     let get_info_expr = quote! { (self.mock_id, &self.scenario) };
+    // Expression giving real implementation calls are forwarded to.
+    let delegate_expr = if has_delegate {
+        match *self_arg {
+            FnArg::SelfRef(ArgSelfRef { mutability: Some(_), .. }) => Some(quote! { self.delegate.as_deref_mut() }),
+            _ => Some(quote! { self.delegate.as_deref() }),
+        }
+    } else {
+        None
+    };
     generate_stub_code(
         mock_type_id,
         &method_ident,
         generics,
         Some(self_arg),
+        delegate_expr,
         get_info_expr,
         args,
         return_type,
-        false,
+        is_unsafe,
+        is_async,
+        record_args,
     )
 }
 
+/// Returns awaited result type for async methods, i.e. methods
+/// declared with `async fn` or returning boxed future.
+fn async_output(is_async: bool, return_type: &Type) -> Option<Type> {
+    if is_async {
+        Some(return_type.clone())
+    } else {
+        boxed_future_output(return_type)
+    }
+}
+
+/// Returns type of box keeping future returned by reaction of async method,
+/// it must be `Send` for methods returning boxed `Send` future.
+fn async_future_box(is_async: bool, return_type: &Type, output: &Type) -> TokenStream {
+    if !is_async && is_send_boxed_future(return_type) {
+        quote! { ::mockers::future::SendBoxFuture<#output> }
+    } else {
+        quote! { ::mockers::future::LocalBoxFuture<#output> }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn generate_stub_code(
     mock_type_id: usize,
     method_ident: &Ident,
     generics: &Generics,
     self_arg: Option<&FnArg>,
+    delegate_expr: Option<TokenStream>,
     get_info_expr: TokenStream,
     args: &Punctuated<FnArg, Token![,]>,
     return_type: &Type,
     is_unsafe: bool,
+    is_async: bool,
+    record_args: bool,
 ) -> Result<TokenStream, String> {
     let method_name = method_ident.to_string();
     // Generate expression returning tuple of all method arguments.
@@ -797,6 +1048,7 @@ fn generate_stub_code(
     }
 
     let verify_fn = Ident::new(&format!("verify{}", args.len()), Span::call_site());
+    let verify_or_delegate_fn = Ident::new(&format!("verify_or_delegate{}", args.len()), Span::call_site());
 
     let mut impl_args: Vec<FnArg> = args
         .iter()
@@ -821,22 +1073,183 @@ fn generate_stub_code(
     } else {
         None
     };
-    let type_ids_expr = gen_type_ids_expr(generics);
+    let type_ids_expr = gen_type_ids_expr(generics, quote! { &scenario });
+    let method_where_clause = &generics.where_clause;
+
+    // Stubs track their caller, so that failures may report where call is made.
+    // `#[track_caller]` has no effect on `async fn`, so they are implemented as
+    // plain functions which get caller before returning future.
+    let caller_expr = if is_async {
+        quote! { Some(caller) }
+    } else {
+        quote! { Some(::std::panic::Location::caller()) }
+    };
 
-    Ok(quote! {
-        #[allow(unused_mut)]
-        #unsafe_t fn #method_ident #generics (#(#impl_args),*) -> #return_type {
-            let (mock_id, scenario) = #get_info_expr;
-            let method_data = ::mockers::MethodData { mock_id: mock_id,
-                                                      mock_type_id: #mock_type_id,
-                                                      method_name: #method_name,
-                                                      type_param_ids: #type_ids_expr };
-            let action = scenario.borrow_mut().#verify_fn(method_data, #(#arg_values),*);
+    // Type of value returned by reaction, async methods get `MockFuture`.
+    let output = async_output(is_async, return_type);
+    let result_type = match output {
+        Some(ref output) => {
+            let future_box = async_future_box(is_async, return_type, output);
+            quote! { ::mockers::future::MockFuture<#output, #future_box> }
+        }
+        None => quote! { #return_type },
+    };
+
+    let record_args = if record_args {
+        generate_args_recorder(args)
+    } else {
+        quote! { None }
+    };
+
+    // Calls not matching any expectation are forwarded to real implementation if there is one.
+    // Result of boxed future methods is wrapped with `MockFuture`, so real result is returned
+    // immediately.
+    let call_code = match delegate_expr {
+        Some(delegate_expr) => {
+            let return_t = if output.is_some() {
+                Some(quote! { return })
+            } else {
+                None
+            };
+            quote! {
+                let delegating = #delegate_expr.is_some();
+                let verified = ::mockers::shared::lock(&scenario)
+                    .#verify_or_delegate_fn(method_data, delegating, #(#arg_values),*);
+                match verified {
+                    Ok(action) => action(),
+                    Err((#(#arg_values,)*)) => #return_t #delegate_expr.unwrap().#method_ident(#(#arg_values),*),
+                }
+            }
+        }
+        None => quote! {
+            let action = ::mockers::shared::lock(&scenario).#verify_fn(method_data, #(#arg_values),*);
             action()
+        },
+    };
+
+    let verify_code = quote! {
+        let (mock_id, scenario) = #get_info_expr;
+        // Get default result factory if result type implements `Default`,
+        // it is used by nice mocks.
+        let default_result = {
+            use ::mockers::mode::{ViaDefault as _, NoDefault as _};
+            (&::mockers::mode::DefaultResultOf::<#result_type>::new()).default_result()
+        };
+        let method_data = ::mockers::MethodData { mock_id: mock_id,
+                                                  mock_type_id: #mock_type_id,
+                                                  method_name: #method_name,
+                                                  type_param_ids: #type_ids_expr,
+                                                  default_result: default_result,
+                                                  record_args: #record_args,
+                                                  caller: #caller_expr };
+        #call_code
+    };
+
+    // Async methods get `MockFuture` from scenario and either await it
+    // or return it boxed. Future of `async fn` doesn't run until polled,
+    // so call is verified on first poll, just like for real `async fn`.
+    let (output_type, body) = match output {
+        None => (quote! { #return_type }, verify_code),
+        Some(_) if is_async => {
+            let body = quote! {
+                let caller = ::std::panic::Location::caller();
+                async move {
+                    let result: #result_type = { #verify_code };
+                    result.await
+                }
+            };
+            (quote! { impl ::std::future::Future<Output = #return_type> }, body)
+        }
+        Some(_) => {
+            let body = quote! {
+                let result: #result_type = { #verify_code };
+                Box::pin(result)
+            };
+            (quote! { #return_type }, body)
+        }
+    };
+
+    Ok(quote! {
+        #[allow(unused_mut, unused_unsafe)]
+        #[track_caller]
+        #unsafe_t fn #method_ident #generics (#(#impl_args),*) -> #output_type #method_where_clause {
+            #body
         }
     })
 }
 
+/// Generate functions saving and restoring method arguments for call recording,
+/// see `mockers::record::ArgsRecorder`. Arguments of `impl Trait` types can't
+/// be named and thus aren't recorded.
+fn generate_args_recorder(args: &Punctuated<FnArg, Token![,]>) -> TokenStream {
+    let arg_types: Vec<&Type> = args
+        .iter()
+        .filter_map(|arg| match *arg {
+            FnArg::Captured(ArgCaptured { ref ty, .. }) => Some(ty),
+            _ => None,
+        })
+        .collect();
+    if arg_types.iter().any(|ty| has_impl_trait(ty)) {
+        return quote! { None };
+    }
+    if arg_types.is_empty() {
+        return quote! {
+            Some(::mockers::record::ArgsRecorder {
+                save: |_| Box::new(()),
+                restore: |_, f| f(&() as *const () as *const u8),
+                saved_type: ::std::any::type_name::<()>,
+            })
+        };
+    }
+
+    let mut saved_types = Vec::new();
+    let mut save_exprs = Vec::new();
+    let mut restore_stmts = Vec::new();
+    let mut restore_exprs = Vec::new();
+    for (i, ty) in arg_types.iter().enumerate() {
+        let index = syn::Index::from(i);
+        match **ty {
+            // References are saved as owned values and borrowed back, mutable
+            // references point to fresh copy, so saved value is never changed.
+            Type::Reference(TypeReference { ref mutability, ref elem, .. }) => {
+                saved_types.push(quote! { <#elem as ::std::borrow::ToOwned>::Owned });
+                save_exprs.push(quote! { <#elem as ::std::borrow::ToOwned>::to_owned(&*args.#index) });
+                let saved = quote! { ::std::borrow::Borrow::<#elem>::borrow(&saved.#index) };
+                if mutability.is_some() {
+                    let owned = Ident::new(&format!("owned{}", i), Span::call_site());
+                    restore_stmts.push(quote! {
+                        let mut #owned = <#elem as ::std::borrow::ToOwned>::to_owned(#saved);
+                    });
+                    restore_exprs.push(quote! { ::std::borrow::BorrowMut::<#elem>::borrow_mut(&mut #owned) });
+                } else {
+                    restore_exprs.push(saved);
+                }
+            }
+            _ => {
+                saved_types.push(quote! { #ty });
+                save_exprs.push(quote! { ::std::clone::Clone::clone(&args.#index) });
+                restore_exprs.push(quote! { ::std::clone::Clone::clone(&saved.#index) });
+            }
+        }
+    }
+
+    quote! {
+        Some(::mockers::record::ArgsRecorder {
+            save: |args_ptr| {
+                let args = unsafe { &*(args_ptr as *const (#(#arg_types,)*)) };
+                Box::new((#(#save_exprs,)*))
+            },
+            restore: |saved, f| {
+                let saved = saved.downcast_ref::<(#(#saved_types,)*)>().unwrap();
+                #(#restore_stmts)*
+                let mut args = (#(#restore_exprs,)*);
+                f(&mut args as *mut _ as *const u8)
+            },
+            saved_type: ::std::any::type_name::<(#(#saved_types,)*)>,
+        })
+    }
+}
+
 /// Generate mock implementation method for creating expectations.
 ///
 /// Returns `ItemImpl` for generated method or `None` in case of errors.
@@ -850,16 +1263,18 @@ fn generate_stub_code(
 /// #[allow(dead_code)]
 /// pub fn bar<Arg0Match: ::mockers::MatchArg<u32>>(&self, arg0: Arg0Match)
 ///  -> ::mockers::CallMatch1<u32, ()> {
-///     ::mockers::CallMatch1::new(self.mock_id, 1usize /* mock_id */,
+///     ::mockers::CallMatch1::new(&self.scenario, self.mock_id, 1usize /* mock_id */,
 ///                                Box::new(arg0))
 /// }
 /// ```
+#[allow(clippy::too_many_arguments)]
 fn generate_impl_method_for_trait(
     mock_type_id: usize,
     method_ident: Ident,
     generics: &Generics,
     args: &Punctuated<FnArg, Token![,]>,
     return_type: &Type,
+    is_async: bool,
     trait_path: &Path,
     mock_path: &Path,
 ) -> Result<TokenStream, String> {
@@ -884,6 +1299,7 @@ fn generate_impl_method_for_trait(
         &generics,
         &fixed_args,
         &fixed_return_type,
+        is_async,
     )
 }
 
@@ -897,7 +1313,7 @@ fn generate_impl_method_for_trait(
 /// #[allow(dead_code)]
 /// pub fn bar<Arg0Match: ::mockers::MatchArg<u32>>(&self, arg0: Arg0Match)
 ///  -> ::mockers::CallMatch1<u32, ()> {
-///     ::mockers::CallMatch1::new(self.mock_id, 1usize /* mock_id */,
+///     ::mockers::CallMatch1::new(&self.scenario, self.mock_id, 1usize /* mock_id */,
 ///                                Box::new(arg0))
 /// }
 /// ```
@@ -907,6 +1323,7 @@ fn generate_impl_method(
     generics: &Generics,
     args: &Punctuated<FnArg, Token![,]>,
     return_type: &Type,
+    is_async: bool,
 ) -> Result<TokenStream, String> {
     // For each argument generate...
     let mut arg_matcher_types = Vec::<TokenStream>::new();
@@ -914,11 +1331,12 @@ fn generate_impl_method(
 
     // Arguments passed to `CallMatchN::new` method inside mock method body.
     let mut new_args = Vec::<TokenStream>::new();
+    new_args.push(quote! { &self.scenario });
     new_args.push(quote! { self.mock_id });
     new_args.push(quote! { #mock_type_id });
     let method_name = method_ident.to_string();
     new_args.push(quote! { #method_name });
-    new_args.push(gen_type_ids_expr(generics).into_token_stream());
+    new_args.push(gen_type_ids_expr(generics, quote! { &self.scenario }).into_token_stream());
 
     // Lifetimes used for reference-type parameters.
     let mut arg_lifetimes = Vec::new();
@@ -966,24 +1384,36 @@ fn generate_impl_method(
 
         // 1. Type parameter
         let match_arg_path = quote! { ::mockers::MatchArg<#new_arg_type>};
-        arg_matcher_types.push(quote! { #arg_type_ident: #match_arg_path + 'static });
+        arg_matcher_types.push(quote! { #arg_type_ident: #match_arg_path + ::mockers::MaybeSend + 'static });
         inputs.push(quote! { #arg_ident: #arg_type_ident });
 
         new_args.push(quote! { Box::new(#arg_ident) });
     }
 
-    let call_match_ident = Ident::new(&format!("CallMatch{}", args.len()), Span::call_site());
+    // Expectations for async methods are set up using awaited result type.
+    let (call_match_name, result_type) = match async_output(is_async, return_type) {
+        Some(output) => ("AsyncCallMatch", output),
+        None => ("CallMatch", return_type.clone()),
+    };
+    let call_match_ident = Ident::new(&format!("{}{}", call_match_name, args.len()), Span::call_site());
 
     let mut call_match_args: Vec<_> = new_arg_types;
-    call_match_args.push(quote! { #return_type });
+    call_match_args.push(quote! { #result_type });
+    if call_match_name == "AsyncCallMatch" {
+        call_match_args.push(async_future_box(is_async, return_type, &result_type));
+    }
     let ret_type = quote! { ::mockers::#call_match_ident<#(#call_match_args),*> };
 
     let output = ret_type.clone();
     let expect_method_name = method_ident.clone();
 
     let debug_param_bound: TypeParamBound = syn::parse_str("::std::fmt::Debug").unwrap();
+    // Lifetime parameters of method are kept, so that argument and result types
+    // may refer to them.
+    let method_lifetimes = generics.lifetimes().map(|l| quote! { #l }).collect::<Vec<_>>();
     let generic_params = [
         &arg_lifetimes[..],
+        &method_lifetimes[..],
         &generics
             .params
             .iter()
@@ -999,10 +1429,16 @@ fn generate_impl_method(
         &arg_matcher_types[..],
     ]
     .concat();
+    // `Self` is handle here, so predicates mentioning it are dropped.
+    let where_clause = generics
+        .where_clause
+        .as_ref()
+        .and_then(|c| make_where_clause(c.predicates.iter().filter(|p| !mentions_self(p)).cloned()));
 
     let impl_subitem: TokenStream = quote! {
-        #[allow(dead_code)]
-        pub fn #expect_method_name<#(#generic_params),*>(&self, #(#inputs),*) -> #output {
+        #[allow(dead_code, clippy::too_many_arguments)]
+        #[track_caller]
+        pub fn #expect_method_name<#(#generic_params),*>(&self, #(#inputs),*) -> #output #where_clause {
             ::mockers::#call_match_ident::new(#(#new_args),*)
         }
     };
@@ -1014,9 +1450,27 @@ fn generate_extern_mock(
     foreign_mod: &syn::ItemForeignMod,
     mock_ident: &Ident,
     handle_ident: &Ident,
-) -> Result<TokenStream, String> {
+    record_args: &RecordArgs,
+) -> Result<TokenStream, Error> {
     let mock_type_id = MOCK_TYPE_ID_GENERATOR.next_id();
 
+    for item in &foreign_mod.items {
+        if let ForeignItem::Fn(ForeignItemFn { ref decl, ref ident, .. }) = item {
+            validate_arg_count(ident, decl)?;
+        }
+    }
+
+    // All functions listed in `record_args` must be declared in extern block.
+    if let RecordArgs::Methods(ref methods) = *record_args {
+        for name in methods {
+            let declared = foreign_mod.items.iter()
+                .any(|item| matches!(item, ForeignItem::Fn(ForeignItemFn { ref ident, .. }) if ident == name));
+            if !declared {
+                return Err(Error::Spanned(name.span(), error::ERR_UNKNOWN_RECORDED_METHOD.to_string()));
+            }
+        }
+    }
+
     let (mock_items, stub_items): (Vec<_>, Vec<_>) = foreign_mod
         .items
         .iter()
@@ -1037,6 +1491,7 @@ fn generate_extern_mock(
                     &decl.generics,
                     &decl.inputs,
                     &ret_ty,
+                    false,
                 )?;
 
                 let get_info_expr = quote! {
@@ -1049,10 +1504,13 @@ fn generate_extern_mock(
                     ident,
                     &decl.generics,
                     None,
+                    None,
                     get_info_expr,
                     &decl.inputs,
                     &ret_ty,
                     true,
+                    false,
+                    record_args.includes(ident),
                 )?;
 
                 Ok((mock_method, stub_method))
@@ -1071,6 +1529,7 @@ fn generate_extern_mock(
 
     let mock_struct = quote! {
         pub struct #mock_ident {
+            scenario: ::mockers::ScenarioRef,
             mock_id: usize,
         }
     };
@@ -1078,7 +1537,7 @@ fn generate_extern_mock(
         impl ::mockers::Mock for #mock_ident {
             type Handle = #handle_ident;
 
-            fn new(id: usize, scenario_int: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>) -> Self {
+            fn new(id: usize, scenario_int: ::mockers::ScenarioRef) -> Self {
                 ::mockers::EXTERN_MOCKS.with(|mocks| {
                     let mut mocks = mocks.borrow_mut();
                     if mocks.contains_key(&#mock_type_id) {
@@ -1087,6 +1546,7 @@ fn generate_extern_mock(
                     mocks.insert(#mock_type_id, (id, scenario_int.clone()));
                 });
                 #mock_ident {
+                    scenario: scenario_int,
                     mock_id: id,
                 }
             }
@@ -1104,7 +1564,7 @@ fn generate_extern_mock(
     };
     let handle_impl = quote! {
         impl ::mockers::MockHandle for #handle_ident {
-            fn new(id: usize, scenario_int: ::std::rc::Rc<::std::cell::RefCell<::mockers::ScenarioInternals>>) -> Self {
+            fn new(id: usize, scenario_int: ::mockers::ScenarioRef) -> Self {
                 #handle_ident {
                     mock_id: id,
                 }
@@ -1135,7 +1595,7 @@ fn generate_extern_mock(
 pub fn mock_impl(input: TokenStream) -> Result<TokenStream, Error> {
     let args = parse_macro_args(input).map_err(|_| "can't parse macro input".to_string())?;
     let tokens = generate_mock_for_traits(args.mock_ident, args.handle_ident, &args.traits, false,
-                                          &DerivedTraits::default())?;
+                                          &DerivedTraits::default(), &HashMap::new(), &RecordArgs::None)?;
 
     #[cfg(feature="debug")] {
         eprintln!("{}", format_code(&tokens));
@@ -1146,8 +1606,10 @@ pub fn mock_impl(input: TokenStream) -> Result<TokenStream, Error> {
 
 /// Generate implementation of supported standard traits for mock and handle structs.
 fn derive_standard_traits(derives: &DerivedTraits, mock_ident: &Ident, handle_ident: &Ident,
-                          type_params: &[TypeParam])
+                          lifetimes: &[LifetimeDef], type_params: &[TypeParam],
+                          where_clause: &Option<WhereClause>)
         -> Vec<TokenStream> {
+    let lifetime_idents = lifetimes.iter().map(|l| l.lifetime.clone()).collect::<Vec<_>>();
     let type_param_idents = type_params.iter().map(|t| t.ident.clone()).collect::<Vec<_>>();
     let mut items = Vec::new();
 
@@ -1156,23 +1618,30 @@ fn derive_standard_traits(derives: &DerivedTraits, mock_ident: &Ident, handle_id
 
         DeriveClone::Normal => {
             items.push(quote! {
-                impl<#(#type_params),*> Clone for #mock_ident<#(#type_param_idents),*> {
+                impl<#(#lifetimes,)* #(#type_params),*> Clone for #mock_ident<#(#lifetime_idents,)* #(#type_param_idents),*>
+                        #where_clause {
+                    #[track_caller]
                     fn clone(&self) -> Self {
                         let method_data = ::mockers::MethodData {
                             mock_id: self.mock_id,
                             mock_type_id: 0usize,
                             method_name: "Clone::clone",
                             type_param_ids: vec![],
+                            default_result: None,
+                            record_args: None,
+                            caller: Some(::std::panic::Location::caller()),
                         };
-                        let action = self.scenario.borrow_mut().verify0(method_data);
+                        let action = ::mockers::shared::lock(&self.scenario).verify0(method_data);
                         action()
                     }
                 }
 
-                impl<#(#type_params),*> ::mockers::CloneMock<#mock_ident<#(#type_param_idents),*>> for #handle_ident<#(#type_params),*> {
+                impl<#(#lifetimes,)* #(#type_params),*> ::mockers::CloneMock<#mock_ident<#(#lifetime_idents,)* #(#type_param_idents),*>>
+                        for #handle_ident<#(#lifetime_idents,)* #(#type_param_idents),*> #where_clause {
                     #[allow(dead_code)]
-                    fn clone(&self) -> ::mockers::CallMatch0<#mock_ident<#(#type_param_idents),*>> {
-                        ::mockers::CallMatch0::new(self.mock_id, 0usize, "Clone::clone", vec![])
+                    #[track_caller]
+                    fn clone(&self) -> ::mockers::CallMatch0<#mock_ident<#(#lifetime_idents,)* #(#type_param_idents),*>> {
+                        ::mockers::CallMatch0::new(&self.scenario, self.mock_id, 0usize, "Clone::clone", vec![])
                     }
                 }
             });
@@ -1180,7 +1649,8 @@ fn derive_standard_traits(derives: &DerivedTraits, mock_ident: &Ident, handle_id
 
         DeriveClone::Shared => {
             items.push(quote! {
-                impl<#(#type_params),*> Clone for #mock_ident<#(#type_param_idents),*> {
+                impl<#(#lifetimes,)* #(#type_params),*> Clone for #mock_ident<#(#lifetime_idents,)* #(#type_param_idents),*>
+                        #where_clause {
                     fn clone(&self) -> Self {
                         use ::mockers::Mock;
                         #mock_ident::new(self.mock_id, self.scenario.clone())
@@ -1193,13 +1663,129 @@ fn derive_standard_traits(derives: &DerivedTraits, mock_ident: &Ident, handle_id
     items
 }
 
+/// Returns whether generics have parameters other than lifetimes. Methods with
+/// lifetime parameters only don't prevent trait from being made into object.
+fn has_type_params(generics: &Generics) -> bool {
+    generics.params.iter().any(|p| !matches!(p, GenericParam::Lifetime(..)))
+}
+
+/// Returns whether where clause has predicates mentioning `Self`.
+fn where_mentions_self(generics: &Generics) -> bool {
+    generics.where_clause.as_ref().is_some_and(|c| c.predicates.iter().any(mentions_self))
+}
+
+/// Returns whether where predicate mentions `Self` anywhere.
+fn mentions_self(predicate: &WherePredicate) -> bool {
+    fn check(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Ident(ref ident) => ident == "Self",
+            TokenTree::Group(ref group) => check(group.stream()),
+            _ => false,
+        })
+    }
+    check(predicate.into_token_stream())
+}
+
+/// Returns whether where predicate is `Self: Sized`.
+fn bounds_self_with_sized(predicate: &WherePredicate) -> bool {
+    match predicate {
+        WherePredicate::Type(PredicateType { bounded_ty, bounds, .. }) => {
+            *bounded_ty == parse_quote! { Self }
+                && bounds.iter().any(|b| match b {
+                    TypeParamBound::Trait(TraitBound { path, .. }) => {
+                        path.segments.last().is_some_and(|s| s.value().ident == "Sized")
+                    }
+                    TypeParamBound::Lifetime(..) => false,
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Returns paths of traits bounding `Self` in where predicate, except `Sized`.
+fn self_trait_bounds(predicate: &WherePredicate) -> Vec<&Path> {
+    match predicate {
+        WherePredicate::Type(PredicateType { bounded_ty, bounds, .. }) if *bounded_ty == parse_quote! { Self } => {
+            bounds.iter()
+                .filter_map(|b| match b {
+                    TypeParamBound::Trait(TraitBound { path, modifier: TraitBoundModifier::None, .. })
+                        if !path.segments.last().is_some_and(|s| s.value().ident == "Sized") => Some(path),
+                    _ => None,
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Removes bounds returned by `self_trait_bounds` from where predicate.
+/// Returns `None` if no bounds are left.
+fn without_self_trait_bounds(predicate: &WherePredicate) -> Option<WherePredicate> {
+    let trait_bounds = self_trait_bounds(predicate);
+    if trait_bounds.is_empty() {
+        return Some(predicate.clone());
+    }
+    let mut predicate = predicate.clone();
+    if let WherePredicate::Type(PredicateType { ref mut bounds, .. }) = predicate {
+        *bounds = bounds.iter()
+            .filter(|b| !matches!(b, TypeParamBound::Trait(TraitBound { path, .. }) if trait_bounds.contains(&path)))
+            .cloned()
+            .collect();
+        if bounds.is_empty() {
+            return None;
+        }
+    }
+    Some(predicate)
+}
+
+/// Creates where clause from predicates, returns `None` if there are none.
+fn make_where_clause<I: IntoIterator<Item = WherePredicate>>(predicates: I) -> Option<WhereClause> {
+    let predicates = Punctuated::<WherePredicate, Token![,]>::from_iter(predicates);
+    if predicates.is_empty() {
+        None
+    } else {
+        Some(parse_quote! { where #predicates })
+    }
+}
+
+/// Replaces given lifetimes with `'static` in syntax item.
+fn make_lifetimes_static<T: ToTokens + Parse>(item: &T, lifetimes: &[Lifetime]) -> T {
+    fn replace(tokens: TokenStream, lifetimes: &[&Ident]) -> TokenStream {
+        let mut after_apostrophe = false;
+        tokens
+            .into_iter()
+            .map(|tt| {
+                let tt = match tt {
+                    TokenTree::Ident(ref ident) if after_apostrophe && lifetimes.contains(&ident) => {
+                        TokenTree::Ident(Ident::new("static", ident.span()))
+                    }
+                    TokenTree::Group(ref group) => {
+                        let mut new_group = Group::new(group.delimiter(), replace(group.stream(), lifetimes));
+                        new_group.set_span(group.span());
+                        TokenTree::Group(new_group)
+                    }
+                    tt => tt,
+                };
+                after_apostrophe = matches!(tt, TokenTree::Punct(ref p) if p.as_char() == '\'');
+                tt
+            })
+            .collect()
+    }
+    let lifetimes = lifetimes.iter().map(|l| &l.ident).collect::<Vec<_>>();
+    syn::parse2(replace(item.into_token_stream(), &lifetimes)).expect("item with replaced lifetimes")
+}
+
 /// Given generic params, returns expression returning vector of type parameter IDs.
-fn gen_type_ids_expr(generics: &Generics) -> Expr {
+/// Failure about unregistered type uses colors chosen for `scenario`.
+fn gen_type_ids_expr(generics: &Generics, scenario: TokenStream) -> Expr {
     let type_param_id_exprs = generics.type_params().map(|p| {
         let TypeParam { ref ident, .. } = p;
         quote!(<MockersTypeRegistry<#ident> as ::mockers::TypeInfo>::get_type_id())
-    });
-    parse_quote!(vec![#(#type_param_id_exprs),*])
+    }).collect::<Vec<_>>();
+    if type_param_id_exprs.is_empty() {
+        return parse_quote!(vec![]);
+    }
+    parse_quote!(::mockers::type_info::type_param_ids(#scenario, || vec![#(#type_param_id_exprs),*]))
 }
 
 fn is_self_arg(arg: &FnArg) -> bool {
@@ -1210,24 +1796,77 @@ fn is_self_arg(arg: &FnArg) -> bool {
     }
 }
 
+/// Checks that method has no more arguments than mocking supports.
+fn validate_arg_count(method_ident: &Ident, decl: &FnDecl) -> Result<(), Error> {
+    let arg_count = decl.inputs.iter().filter(|arg| !is_self_arg(arg)).count();
+    if arg_count > MAX_ARGS {
+        return Err(Error::Spanned(method_ident.span(), error::ERR_TOO_MANY_ARGUMENTS.to_string()));
+    }
+    Ok(())
+}
+
 /// Checks whether type parameter bounds are supported.
 /// Returns passed type parameter reference on success.
-fn validate_type_param_bounds(param: &TypeParam) -> Result<&TypeParam, Error> {
-    validate_type_bounds(&param.bounds).map(|_| param)
+fn validate_type_param_bounds<'p>(param: &'p TypeParam, lifetimes: &[LifetimeDef]) -> Result<&'p TypeParam, Error> {
+    validate_type_bounds(&param.bounds, lifetimes).map(|_| param)
 }
 
-/// Checks whether associated type bounds are supported.
+/// Checks whether associated type bounds are supported. Generic associated
+/// types may have lifetime parameters only, lifetime bounds may refer to them too.
 /// Returns passed associated type reference on success.
-fn validate_trait_item_type_bounds(assoc_type: &TraitItemType) -> Result<&TraitItemType, Error> {
-    validate_type_bounds(&assoc_type.bounds).map(|_| assoc_type)
+fn validate_trait_item_type_bounds<'t>(assoc_type: &'t TraitItemType, lifetimes: &[LifetimeDef])
+        -> Result<&'t TraitItemType, Error> {
+    if let Some(param) = assoc_type.generics.params.iter().find(|p| !matches!(p, GenericParam::Lifetime(..))) {
+        return Err(Error::Spanned(param.span(), error::ERR_GENERIC_ASSOCIATED_TYPE_PARAMS_NOT_SUPPORTED.to_string()));
+    }
+    // Associated type is turned into single `'static` type parameter, so its own lifetimes
+    // are replaced with `'static`. It is fine for `'a` bound, but changes meaning of
+    // trait bounds, e.g. `Iterator<Item = &'a u8>`, which then can't be implemented.
+    let own_lifetimes = assoc_type.generics.lifetimes().map(|l| l.lifetime.clone()).collect::<Vec<_>>();
+    for bound in &assoc_type.bounds {
+        if let TypeParamBound::Trait(_) = bound {
+            if let Some(lt) = find_lifetime(bound.into_token_stream(), &own_lifetimes) {
+                return Err(Error::Spanned(lt, error::ERR_ASSOCIATED_TYPE_LIFETIME_IN_TRAIT_BOUND.to_string()));
+            }
+        }
+    }
+    let lifetimes = lifetimes.iter().chain(assoc_type.generics.lifetimes()).cloned().collect::<Vec<_>>();
+    validate_type_bounds(&assoc_type.bounds, &lifetimes).map(|_| assoc_type)
+}
+
+/// Finds first use of any of given lifetimes in token stream.
+/// Returns span of found lifetime.
+fn find_lifetime(tokens: TokenStream, lifetimes: &[Lifetime]) -> Option<Span> {
+    let mut apostrophe = None;
+    for tt in tokens {
+        match tt {
+            TokenTree::Ident(ref ident) if apostrophe.is_some() && lifetimes.iter().any(|l| l.ident == *ident) => {
+                return apostrophe;
+            }
+            TokenTree::Group(ref group) => {
+                if let Some(span) = find_lifetime(group.stream(), lifetimes) {
+                    return Some(span);
+                }
+            }
+            _ => (),
+        }
+        apostrophe = match tt {
+            TokenTree::Punct(ref p) if p.as_char() == '\'' => Some(p.span()),
+            _ => None,
+        };
+    }
+    None
 }
 
-/// Checks whether type bounds are supported.
-fn validate_type_bounds(bounds: &Punctuated<TypeParamBound, Token![+]>) -> Result<(), Error> {
+/// Checks whether type bounds are supported. Lifetime bounds must be either
+/// `'static` or one of trait lifetime parameters, because mock has no others.
+fn validate_type_bounds(bounds: &Punctuated<TypeParamBound, Token![+]>, lifetimes: &[LifetimeDef])
+        -> Result<(), Error> {
     for bound in bounds {
         match bound {
             TypeParamBound::Trait(_) => (),
             TypeParamBound::Lifetime(lt) if lt.ident == "static" => (),
+            TypeParamBound::Lifetime(lt) if lifetimes.iter().any(|l| l.lifetime == *lt) => (),
             TypeParamBound::Lifetime(lt) =>
                 return Err(Error::Spanned(lt.apostrophe, error::ERR_ONLY_STATIC_LIFETIME_BOUND_IS_SUPPORTED.to_string())),
         }
@@ -1236,12 +1875,20 @@ fn validate_type_bounds(bounds: &Punctuated<TypeParamBound, Token![+]>) -> Resul
     Ok(())
 }
 
-/// Converts associated type to type parameter
+/// Converts associated type to type parameter.
+/// Type parameter is the same for all lifetimes of generic associated type, so
+/// it is required to be `'static` and lifetime parameters are replaced with `'static`
+/// in bounds, e.g. `type Iter<'a>: Debug + 'a` gives `Iter: Debug + 'static`.
 fn assoc_type_to_type_param(assoc_type: &TraitItemType) -> TypeParam {
-    let TraitItemType { ident, bounds, .. } = assoc_type;
-    if !bounds.is_empty() {
-        parse_quote!{ #ident: #bounds }
+    let TraitItemType { ident, generics, bounds, .. } = assoc_type;
+    let mut param: TypeParam = if !bounds.is_empty() {
+        let lifetimes = generics.lifetimes().map(|l| l.lifetime.clone()).collect::<Vec<_>>();
+        make_lifetimes_static(&parse_quote!{ #ident: #bounds }, &lifetimes)
     } else {
         parse_quote!{ #ident }
+    };
+    if generics.lifetimes().next().is_some() {
+        param.bounds.push(parse_quote! { 'static });
     }
+    param
 }
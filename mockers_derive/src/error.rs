@@ -30,8 +30,6 @@ pub const ERR_MOCK_NAME_REQUIRED_FOR_EXTERN: &str = indoc!("
 pub const ERR_TRAITS_AND_EXTERN_ONLY_ARE_SUPPORTED: &str =
     "Attribute may be used on traits and extern blocks only";
 
-pub const ERR_LIFETIME_BOUNDS_NOT_SUPPORTED: &str = "Lifetime bounds aren't supported yet";
-
 pub const ERR_PARENT_TRAIT_NOT_REFERENCED: &str = indoc!(r#"
     Unfortunately, macro can't get full path to referenced parent trait, so it must be be given using 'refs' parameter:
 
@@ -56,30 +54,30 @@ pub const ERR_REFERENCED_TRAIT_NOT_FOUND: &str = indoc!(r#"
 
     "#);
 
-pub const ERR_UNSAFE_TRAITS_NOT_SUPPORTED: &str = "Unsafe traits are not supported yet.\n";
-
-pub const ERR_LIFETIME_PARAMS_NOT_SUPPORTED: &str = "Lifetime parameters are not supported yet\n";
-
 pub const ERR_CONST_PARAMS_NOT_SUPPORTED: &str = "Const parameters are not supported yet\n";
 
-pub const ERR_WHERE_CLAUSES_NOT_SUPPORTED: &str = "Where clauses are not supported yet.\n";
-
 pub const ERR_NO_BASE_TRAIT_DEFINITIONS: &str = "All base trait definitions must be provided";
 
 pub const ERR_TYPE_BOUND_MODIFIERS_NOT_SUPPORTED: &str = "Type bound modifiers are not supported yet";
 
-pub const ERR_LIFETIME_PARAM_BOUNDS_NOT_SUPPORTED: &str = "Lifetime parameter bounds are not supported yet";
+pub const ERR_GENERIC_ASSOCIATED_TYPE_PARAMS_NOT_SUPPORTED: &str =
+    "only lifetime parameters are supported for generic associated types";
 
-pub const ERR_ASSOCIATED_TYPE_BOUNDS_NOT_SUPPORTED: &str = "Associated type bounds are not supported yet.\n";
-
-pub const ERR_UNSAFE_TRAIT_METHODS_NOT_SUPPORTED: &str = "Unsafe trait methods are not supported.\n";
+pub const ERR_TOO_MANY_ARGUMENTS: &str = "Methods with more than 12 arguments are not supported.\n";
 
 pub const ERR_EXTERN_METHODS_NOT_SUPPORTED: &str = "Extern specification for trait methods is not supported.\n";
 
-pub const ERR_TRAIT_CONST_NOT_SUPPORTED: &str = "trait constants are not supported yet";
+pub const ERR_NO_CONST_VALUE: &str =
+    "trait constant has no default value, give it with `#[mocked(consts(NAME = value))]`";
+
+pub const ERR_UNKNOWN_CONST: &str = "trait has no constant with this name";
+
+pub const ERR_UNKNOWN_RECORDED_METHOD: &str = "there is no method with this name to record arguments of";
 
 pub const ERR_TRAIT_MACROS_NOT_SUPPORTED: &str = "trait macros are not supported yet";
 
 pub const ERR_VERBATIM_ITEMS_NOT_SUPPORTED: &str = "verbatim trait items are not supported";
 
-pub const ERR_ONLY_STATIC_LIFETIME_BOUND_IS_SUPPORTED: &str = "only 'static lifetime is supported for type parameters and associated types";
+pub const ERR_ONLY_STATIC_LIFETIME_BOUND_IS_SUPPORTED: &str = "only 'static lifetime and trait lifetime parameters are supported as bounds of type parameters and associated types";
+
+pub const ERR_ASSOCIATED_TYPE_LIFETIME_IN_TRAIT_BOUND: &str = "lifetime parameters of generic associated types may be used in lifetime bounds only, not in trait bounds";
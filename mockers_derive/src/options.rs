@@ -1,8 +1,8 @@
 /// Macro options and parser for it.
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use proc_macro2::{Span, TokenStream};
-use syn::{parse::ParseStream, punctuated::Punctuated, Ident, ItemTrait, Meta, MetaNameValue, NestedMeta, Path, Token, MetaList};
+use syn::{parse::ParseStream, punctuated::Punctuated, Ident, ItemTrait, Lit, Meta, MetaNameValue, NestedMeta, Path, Token, MetaList};
 use indoc::indoc;
 
 use crate::util::is_path_absolute;
@@ -28,6 +28,24 @@ impl Default for DerivedTraits {
     }
 }
 
+/// Methods whose arguments are recorded for inspecting calls after they are made.
+pub enum RecordArgs {
+    None,
+    /// All methods, given as `record_args`.
+    All,
+    /// Listed methods only, given as `record_args(put, get)`.
+    Methods(HashSet<Ident>),
+}
+impl RecordArgs {
+    pub fn includes(&self, method: &Ident) -> bool {
+        match self {
+            RecordArgs::None => false,
+            RecordArgs::All => true,
+            RecordArgs::Methods(methods) => methods.contains(method),
+        }
+    }
+}
+
 pub enum Location {
     /// Attribute is used on actual trait definition. All generated items will
     /// refer to this trait just by name, because they are placed right next to it,
@@ -60,6 +78,10 @@ pub struct MockAttrOptions {
     pub location: Location,
     pub refs: HashMap<Path, Path>,
     pub derives: DerivedTraits,
+    /// Values of trait associated constants, given as
+    /// `consts(NAME = "fake", MAX_LEN = 16)`.
+    pub consts: HashMap<Ident, Lit>,
+    pub record_args: RecordArgs,
 
     /// Print expansion of macro attribute to stderr during build.
     pub debug: bool,
@@ -71,6 +93,8 @@ impl syn::parse::Parse for MockAttrOptions {
         let mut module_path: Option<Path> = None;
         let mut refs: HashMap<Path, Path> = HashMap::new();
         let mut derives: DerivedTraits = DerivedTraits::default();
+        let mut consts: HashMap<Ident, Lit> = HashMap::new();
+        let mut record_args = RecordArgs::None;
         let mut is_extern: bool = false;
         let mut debug: bool = false;
 
@@ -202,6 +226,63 @@ impl syn::parse::Parse for MockAttrOptions {
                         }
                     }
 
+                    NestedMeta::Meta(Meta::List(MetaList {
+                        ident: ref name,
+                        nested: ref items,
+                        ..
+                    })) if name == "consts" => {
+                        use syn::spanned::Spanned;
+
+                        for item in items {
+                            match item {
+                                NestedMeta::Meta(Meta::NameValue(MetaNameValue { ident, lit, .. })) => {
+                                    if consts.insert(ident.clone(), lit.clone()).is_some() {
+                                        return Err(syn::Error::new(
+                                            ident.span(),
+                                            "value of constant is given more than once".to_string(),
+                                        ));
+                                    }
+                                }
+                                _ => return Err(syn::Error::new(
+                                    item.span(),
+                                    indoc!("constant value expected, for example:
+
+                                           #[mocked(consts(MAX_LEN = 16))]\
+                                           ").to_string(),
+                                )),
+                            }
+                        }
+                    }
+
+                    NestedMeta::Meta(Meta::Word(ref ident)) if ident == "record_args" => {
+                        record_args = RecordArgs::All;
+                    }
+
+                    NestedMeta::Meta(Meta::List(MetaList {
+                        ident: ref name,
+                        nested: ref items,
+                        ..
+                    })) if name == "record_args" => {
+                        use syn::spanned::Spanned;
+
+                        let mut methods = HashSet::new();
+                        for item in items {
+                            match item {
+                                NestedMeta::Meta(Meta::Word(ident)) => {
+                                    methods.insert(ident.clone());
+                                }
+                                _ => return Err(syn::Error::new(
+                                    item.span(),
+                                    indoc!("method name expected, for example:
+
+                                           #[mocked(record_args(put, get))]\
+                                           ").to_string(),
+                                )),
+                            }
+                        }
+                        record_args = RecordArgs::Methods(methods);
+                    }
+
                     NestedMeta::Meta(Meta::Word(ref ident)) if ident == "debug" => {
                         debug = true;
                     }
@@ -239,6 +320,8 @@ impl syn::parse::Parse for MockAttrOptions {
             location,
             refs,
             derives,
+            consts,
+            record_args,
             debug,
         })
     }
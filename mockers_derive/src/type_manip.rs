@@ -1,11 +1,13 @@
 ///! Routines for manipulating `syn::Type`s.
 
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream, TokenTree};
+use proc_quote::ToTokens as _;
 use syn::{
     parse_quote, punctuated::Punctuated, AngleBracketedGenericArguments, BareFnArg, Binding,
     GenericArgument, ParenthesizedGenericArguments, Path, PathArguments, PathSegment, QSelf,
-    ReturnType, Token, Type, TypeArray, TypeBareFn, TypeGroup, TypeParen, TypePath, TypePtr,
-    TypeReference, TypeSlice, TypeTuple,
+    ReturnType, Token, TraitBound, Type, TypeArray, TypeBareFn, TypeGroup, TypeImplTrait,
+    TypeParamBound, TypeParen, TypePath, TypePtr, TypeReference, TypeSlice, TypeTraitObject,
+    TypeTuple,
 };
 
 use std::iter::FromIterator as _;
@@ -81,8 +83,14 @@ where
                     })
                 }
             }
-            t @ Type::TraitObject(..) => t.clone(),
-            Type::ImplTrait(ref bounds) => Type::ImplTrait(bounds.clone()),
+            Type::TraitObject(ref t) => Type::TraitObject(TypeTraitObject {
+                bounds: process_bounds(&t.bounds, func),
+                ..t.clone()
+            }),
+            Type::ImplTrait(ref t) => Type::ImplTrait(TypeImplTrait {
+                bounds: process_bounds(&t.bounds, func),
+                ..t.clone()
+            }),
             Type::Paren(ref t) => Type::Paren(TypeParen {
                 elem: Box::new(process_ty(&t.elem, func)),
                 paren_token: syn::token::Paren(Span::call_site()),
@@ -114,6 +122,21 @@ where
             ReturnType::Type(a, ref ty) => ReturnType::Type(a, Box::new(process_ty(&ty, func))),
         }
     }
+    fn process_bounds<Func>(
+        bounds: &Punctuated<TypeParamBound, Token![+]>,
+        func: &Func,
+    ) -> Punctuated<TypeParamBound, Token![+]>
+    where
+        Func: Fn(&syn::PathSegment, &[syn::PathSegment]) -> Type,
+    {
+        Punctuated::from_iter(bounds.iter().map(|bound| match bound {
+            TypeParamBound::Trait(t) => TypeParamBound::Trait(TraitBound {
+                path: process_path(&t.path, func),
+                ..t.clone()
+            }),
+            TypeParamBound::Lifetime(..) => bound.clone(),
+        }))
+    }
     fn process_path<Func>(path: &Path, func: &Func) -> Path
     where
         Func: Fn(&syn::PathSegment, &[syn::PathSegment]) -> Type,
@@ -204,3 +227,92 @@ pub fn set_self(ty: &Type, mock_struct_path: &Path) -> Type {
         },
     )
 }
+
+/// Returns bounds of trait object in `Pin<Box<dyn ...>>` type.
+///
+/// Types are matched by name of last path segment only, so both
+/// `Pin<...>` and `::std::pin::Pin<...>` are recognized.
+fn pinned_trait_object_bounds(ty: &Type) -> Option<&Punctuated<TypeParamBound, Token![+]>> {
+    fn single_type_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+        let segment = match ty {
+            Type::Path(TypePath { qself: None, path }) => path.segments.last()?.into_value(),
+            _ => return None,
+        };
+        if segment.ident != name {
+            return None;
+        }
+        match segment.arguments {
+            PathArguments::AngleBracketed(ref data) if data.args.len() == 1 => {
+                match data.args.first()?.into_value() {
+                    GenericArgument::Type(t) => Some(t),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    let boxed = single_type_arg(ty, "Pin")?;
+    match single_type_arg(boxed, "Box")? {
+        Type::TraitObject(t) => Some(&t.bounds),
+        _ => None,
+    }
+}
+
+/// Returns output type of boxed future, i.e. `T` for
+/// `Pin<Box<dyn Future<Output = T> + Send>>`.
+pub fn boxed_future_output(ty: &Type) -> Option<Type> {
+    pinned_trait_object_bounds(ty)?.iter().filter_map(|bound| match bound {
+        TypeParamBound::Trait(t) => t.path.segments.last(),
+        TypeParamBound::Lifetime(..) => None,
+    }).filter(|segment| segment.value().ident == "Future").filter_map(|segment| {
+        match segment.value().arguments {
+            PathArguments::AngleBracketed(ref data) => data.args.iter().filter_map(|arg| match arg {
+                GenericArgument::Binding(b) if b.ident == "Output" => Some(b.ty.clone()),
+                _ => None,
+            }).next(),
+            _ => None,
+        }
+    }).next()
+}
+
+/// Returns whether boxed future must be `Send`, i.e. whether it is
+/// `Pin<Box<dyn Future<Output = T> + Send>>`.
+pub fn is_send_boxed_future(ty: &Type) -> bool {
+    pinned_trait_object_bounds(ty).is_some_and(|bounds| bounds.iter().any(|bound| match bound {
+        TypeParamBound::Trait(t) => t.path.segments.last().is_some_and(|s| s.value().ident == "Send"),
+        TypeParamBound::Lifetime(..) => false,
+    }))
+}
+
+/// Returns whether type contains `impl Trait` types, which can't be named.
+pub fn has_impl_trait(ty: &Type) -> bool {
+    fn check(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Ident(ref ident) => ident == "impl",
+            TokenTree::Group(ref group) => check(group.stream()),
+            _ => false,
+        })
+    }
+    check(ty.into_token_stream())
+}
+
+/// Returns whether type refers to `Self` itself rather than to its associated
+/// types, i.e. `Self` or `Box<Self>`, but not `Self::Item`.
+pub fn mentions_self_type(ty: &Type) -> bool {
+    fn check(tokens: TokenStream) -> bool {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(tt) = tokens.next() {
+            match tt {
+                TokenTree::Ident(ref ident) if ident == "Self" => match tokens.peek() {
+                    Some(TokenTree::Punct(ref p)) if p.as_char() == ':' => {}
+                    _ => return true,
+                },
+                TokenTree::Group(ref group) if check(group.stream()) => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+    check(ty.into_token_stream())
+}
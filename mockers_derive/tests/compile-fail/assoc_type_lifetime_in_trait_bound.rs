@@ -0,0 +1,9 @@
+use mockers_derive::mocked;
+
+#[mocked]
+trait A {
+    type Iter<'a>: Iterator<Item = &'a u8> where Self: 'a;
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+}
+
+fn main() {}
@@ -2,7 +2,8 @@ use mockers_derive::mocked;
 
 #[mocked]
 trait A {
-    type Item : Sized;
+    type Item<T>;
+    fn foo(&self);
 }
 
 fn main() {}
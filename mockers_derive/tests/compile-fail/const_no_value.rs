@@ -2,7 +2,8 @@ use mockers_derive::mocked;
 
 #[mocked]
 trait A {
-    unsafe fn foo();
+    const NAME: &'static str;
+    fn foo(&self);
 }
 
 fn main() {}
@@ -0,0 +1,9 @@
+use mockers_derive::mocked;
+
+#[mocked(consts(NAME = "fake", LEN = 16))]
+trait A {
+    const NAME: &'static str;
+    fn foo(&self);
+}
+
+fn main() {}
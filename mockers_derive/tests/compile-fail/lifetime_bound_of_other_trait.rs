@@ -0,0 +1,23 @@
+use mockers_derive::mock;
+
+pub trait A<'a> {
+    type Item: 'a;
+}
+
+pub trait B {
+    fn foo(&self);
+}
+
+mock! {
+    ABMock,
+    self,
+    trait A<'a> {
+        type Item: 'a;
+    },
+    self,
+    trait B {
+        fn foo(&self);
+    }
+}
+
+fn main() {}
@@ -1,6 +0,0 @@
-use mockers_derive::mocked;
-
-#[mocked]
-trait A<'a> {}
-
-fn main() {}
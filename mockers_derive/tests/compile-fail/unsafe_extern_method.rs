@@ -1,7 +1,8 @@
 use mockers_derive::mocked;
 
 #[mocked]
-trait A: 'static {
+unsafe trait A {
+    unsafe extern "C" fn foo();
 }
 
 fn main() {}
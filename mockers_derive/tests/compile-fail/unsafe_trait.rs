@@ -1,6 +0,0 @@
-use mockers_derive::mocked;
-
-#[mocked]
-unsafe trait A {}
-
-fn main() {}
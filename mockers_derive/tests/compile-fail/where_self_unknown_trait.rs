@@ -0,0 +1,10 @@
+use mockers_derive::mocked;
+
+pub trait B {}
+
+#[mocked]
+pub trait A where Self: B {
+    fn foo(&self);
+}
+
+fn main() {}
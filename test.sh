@@ -6,6 +6,8 @@ set -e
 
 (echo mockers/stable ; cd mockers && cargo +stable build)
 (echo mockers/stable/test ; cd mockers && cargo +stable test)
+(echo mockers/stable/sync/test ; cd mockers && cargo +stable test --features sync)
+(echo mockers/stable/json/test ; cd mockers && cargo +stable test --features json)
 (echo mockers_derive/stable ; cd mockers_derive && cargo +stable build)
 # `mockers` has more features on nightly Rust.
 (echo mockers/nightly ; cd mockers && cargo +nightly build --features nightly)